@@ -37,6 +37,20 @@ pub fn validate_header_base_fee(
     Ok(())
 }
 
+/// Ensure the EIP-1559 base fee, if set, does not exceed the absolute maximum `max`.
+///
+/// Unlike [`validate_against_parent_eip1559_base_fee`], this does not depend on the parent, so it
+/// also catches corrupt headers whose parent is corrupt as well.
+#[inline]
+pub fn validate_base_fee_bounds(header: &SealedHeader, max: u128) -> Result<(), ConsensusError> {
+    if let Some(base_fee) = header.base_fee_per_gas {
+        if base_fee as u128 > max {
+            return Err(ConsensusError::BaseFeeTooHigh { base_fee, max })
+        }
+    }
+    Ok(())
+}
+
 /// Validate a block without regard for state:
 ///
 /// - Compares the ommer hash in the block header to the block body
@@ -62,45 +76,66 @@ pub fn validate_block_pre_execution(
 
     // EIP-4895: Beacon chain push withdrawals as operations
     if chain_spec.is_shanghai_active_at_timestamp(block.timestamp) {
-        let withdrawals =
-            block.withdrawals.as_ref().ok_or(ConsensusError::BodyWithdrawalsMissing)?;
-        let withdrawals_root = reth_primitives::proofs::calculate_withdrawals_root(withdrawals);
-        let header_withdrawals_root =
-            block.withdrawals_root.as_ref().ok_or(ConsensusError::WithdrawalsRootMissing)?;
-        if withdrawals_root != *header_withdrawals_root {
-            return Err(ConsensusError::BodyWithdrawalsRootDiff(
-                GotExpected { got: withdrawals_root, expected: *header_withdrawals_root }.into(),
-            ))
-        }
+        validate_shanghai_withdrawals(block)?;
     }
 
     // EIP-4844: Shard Blob Transactions
     if chain_spec.is_cancun_active_at_timestamp(block.timestamp) {
-        // Check that the blob gas used in the header matches the sum of the blob gas used by each
-        // blob tx
-        let header_blob_gas_used = block.blob_gas_used.ok_or(ConsensusError::BlobGasUsedMissing)?;
-        let total_blob_gas = block.blob_gas_used();
-        if total_blob_gas != header_blob_gas_used {
-            return Err(ConsensusError::BlobGasUsedDiff(GotExpected {
-                got: header_blob_gas_used,
-                expected: total_blob_gas,
-            }))
-        }
+        validate_cancun_gas(block)?;
     }
 
     // EIP-7685: General purpose execution layer requests
     if chain_spec.is_prague_active_at_timestamp(block.timestamp) {
-        let requests = block.requests.as_ref().ok_or(ConsensusError::BodyRequestsMissing)?;
-        let requests_root = reth_primitives::proofs::calculate_requests_root(&requests.0);
-        let header_requests_root =
-            block.requests_root.as_ref().ok_or(ConsensusError::RequestsRootMissing)?;
-        if requests_root != *header_requests_root {
-            return Err(ConsensusError::BodyRequestsRootDiff(
-                GotExpected { got: requests_root, expected: *header_requests_root }.into(),
-            ))
-        }
+        validate_prague_requests(block)?;
+    }
+
+    Ok(())
+}
+
+/// Validates that the withdrawals root in the block header matches the withdrawals of the block
+/// body, as required from Shanghai.
+#[inline]
+pub fn validate_shanghai_withdrawals(block: &SealedBlock) -> Result<(), ConsensusError> {
+    let withdrawals = block.withdrawals.as_ref().ok_or(ConsensusError::BodyWithdrawalsMissing)?;
+    let withdrawals_root = reth_primitives::proofs::calculate_withdrawals_root(withdrawals);
+    let header_withdrawals_root =
+        block.withdrawals_root.as_ref().ok_or(ConsensusError::WithdrawalsRootMissing)?;
+    if withdrawals_root != *header_withdrawals_root {
+        return Err(ConsensusError::BodyWithdrawalsRootDiff(
+            GotExpected { got: withdrawals_root, expected: *header_withdrawals_root }.into(),
+        ))
     }
+    Ok(())
+}
 
+/// Validates that the blob gas used in the block header matches the sum of the blob gas used by
+/// each blob transaction of the block body, as required from Cancun.
+#[inline]
+pub fn validate_cancun_gas(block: &SealedBlock) -> Result<(), ConsensusError> {
+    let header_blob_gas_used = block.blob_gas_used.ok_or(ConsensusError::BlobGasUsedMissing)?;
+    let total_blob_gas = block.blob_gas_used();
+    if total_blob_gas != header_blob_gas_used {
+        return Err(ConsensusError::BlobGasUsedDiff(GotExpected {
+            got: total_blob_gas,
+            expected: header_blob_gas_used,
+        }))
+    }
+    Ok(())
+}
+
+/// Validates that the requests root in the block header matches the requests of the block body,
+/// as required from Prague.
+#[inline]
+pub fn validate_prague_requests(block: &SealedBlock) -> Result<(), ConsensusError> {
+    let requests = block.requests.as_ref().ok_or(ConsensusError::BodyRequestsMissing)?;
+    let requests_root = reth_primitives::proofs::calculate_requests_root(&requests.0);
+    let header_requests_root =
+        block.requests_root.as_ref().ok_or(ConsensusError::RequestsRootMissing)?;
+    if requests_root != *header_requests_root {
+        return Err(ConsensusError::BodyRequestsRootDiff(
+            GotExpected { got: requests_root, expected: *header_requests_root }.into(),
+        ))
+    }
     Ok(())
 }
 
@@ -526,9 +561,23 @@ mod tests {
         assert_eq!(
             validate_block_pre_execution(&block, &chain_spec),
             Err(ConsensusError::BlobGasUsedDiff(GotExpected {
-                got: 1,
-                expected: expected_blob_gas_used
+                got: expected_blob_gas_used,
+                expected: 1
             }))
         );
     }
+
+    #[test]
+    fn base_fee_bounds() {
+        let header =
+            |base_fee_per_gas| Header { base_fee_per_gas, ..Default::default() }.seal_slow();
+
+        assert_eq!(validate_base_fee_bounds(&header(None), 100), Ok(()));
+        assert_eq!(validate_base_fee_bounds(&header(Some(100)), 100), Ok(()));
+        assert_eq!(
+            validate_base_fee_bounds(&header(Some(101)), 100),
+            Err(ConsensusError::BaseFeeTooHigh { base_fee: 101, max: 100 })
+        );
+        assert_eq!(validate_base_fee_bounds(&header(Some(u64::MAX)), u128::MAX), Ok(()));
+    }
 }
@@ -15,14 +15,16 @@ use reth_primitives::{
     B256, U256,
 };
 
+use core::{any::Any, fmt::Display};
+
 #[cfg(feature = "std")]
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
 #[cfg(not(feature = "std"))]
 extern crate alloc;
 
 #[cfg(not(feature = "std"))]
-use alloc::{fmt::Debug, vec::Vec};
+use alloc::{fmt::Debug, sync::Arc, vec::Vec};
 
 /// A consensus implementation that does nothing.
 pub mod noop;
@@ -223,6 +225,15 @@ pub enum ConsensusError {
     #[error("base fee missing")]
     BaseFeeMissing,
 
+    /// Error when the base fee exceeds the configured absolute maximum.
+    #[error("base fee {base_fee} exceeds maximum {max}")]
+    BaseFeeTooHigh {
+        /// The block's base fee.
+        base_fee: u64,
+        /// The maximum allowed base fee.
+        max: u128,
+    },
+
     /// Error when there is a transaction signer recovery error.
     #[error("transaction signer recovery error")]
     TransactionSignerRecoveryError,
@@ -387,15 +398,64 @@ pub enum ConsensusError {
         /// The block's timestamp.
         timestamp: u64,
     },
+
+    /// Chain specific error, e.g. an L2 error, see [`ConsensusError::custom`].
+    #[error("{0}")]
+    Custom(Arc<dyn CustomConsensusError>),
 }
 
 impl ConsensusError {
+    /// Wraps a chain specific error.
+    pub fn custom(err: impl CustomConsensusError) -> Self {
+        Self::Custom(Arc::new(err))
+    }
+
     /// Returns `true` if the error is a state root error.
     pub const fn is_state_root_error(&self) -> bool {
         matches!(self, Self::BodyStateRootDiff(_))
     }
+
+    /// Returns the chain specific error if it is of type `T`, see [`ConsensusError::Custom`].
+    pub fn as_custom<T: CustomConsensusError>(&self) -> Option<&T> {
+        match self {
+            Self::Custom(err) => (**err).as_any().downcast_ref(),
+            _ => None,
+        }
+    }
 }
 
+/// A chain specific consensus error, carried by [`ConsensusError::Custom`].
+///
+/// Implemented for all comparable errors, so that [`ConsensusError`] stays comparable.
+pub trait CustomConsensusError: Debug + Display + Send + Sync + 'static {
+    /// Returns the error as [`Any`], to downcast it to its concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    /// Returns `true` if `other` is of the same type and equal to this error.
+    fn dyn_eq(&self, other: &dyn CustomConsensusError) -> bool;
+}
+
+impl<T> CustomConsensusError for T
+where
+    T: Debug + Display + PartialEq + Send + Sync + 'static,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn dyn_eq(&self, other: &dyn CustomConsensusError) -> bool {
+        other.as_any().downcast_ref::<T>() == Some(self)
+    }
+}
+
+impl PartialEq for dyn CustomConsensusError {
+    fn eq(&self, other: &Self) -> bool {
+        self.dyn_eq(other)
+    }
+}
+
+impl Eq for dyn CustomConsensusError {}
+
 /// `HeaderConsensusError` combines a `ConsensusError` with the `SealedHeader` it relates to.
 #[derive(thiserror_no_std::Error, Debug)]
 #[error("Consensus error: {0}, Invalid header: {1:?}")]
@@ -5,7 +5,10 @@ use alloy_rlp::{RlpDecodable, RlpDecodableWrapper, RlpEncodable, RlpEncodableWra
 use reth_codecs_derive::{add_arbitrary_tests, derive_arbitrary};
 #[cfg(any(test, feature = "arbitrary"))]
 use reth_primitives::generate_valid_header;
-use reth_primitives::{BlockBody, BlockHashOrNumber, Header, HeadersDirection, B256};
+use reth_primitives::{
+    Block, BlockBody, BlockHashOrNumber, GotExpected, Header, HeadersDirection, SealedBlock, B256,
+};
+use std::collections::HashSet;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
@@ -68,6 +71,172 @@ impl<'a> arbitrary::Arbitrary<'a> for BlockHeaders {
     }
 }
 
+impl BlockHeaders {
+    /// Checks that the headers are a valid response to the given [`GetBlockHeaders`] request:
+    ///
+    /// - at most `limit` headers are returned
+    /// - the first header is the requested start block
+    /// - consecutive headers follow the requested direction, `skip` blocks apart
+    ///
+    /// An empty response always matches, since peers may not have the requested headers.
+    pub fn matches_request(&self, req: &GetBlockHeaders) -> Result<(), HeadersResponseError> {
+        if self.0.len() as u64 > req.limit {
+            return Err(HeadersResponseError::TooMany { got: self.0.len(), limit: req.limit })
+        }
+
+        let Some(first) = self.0.first() else { return Ok(()) };
+        let start_matches = match req.start_block {
+            BlockHashOrNumber::Hash(hash) => first.hash_slow() == hash,
+            BlockHashOrNumber::Number(number) => first.number == number,
+        };
+        if !start_matches {
+            return Err(HeadersResponseError::UnexpectedStart { got: first.number })
+        }
+
+        let stride = req.skip as u64 + 1;
+        for (index, pair) in self.0.windows(2).enumerate() {
+            let (prev, next) = (pair[0].number, pair[1].number);
+            let expected = match req.direction {
+                HeadersDirection::Rising => prev.checked_add(stride),
+                HeadersDirection::Falling => prev.checked_sub(stride),
+            };
+            if expected == Some(next) {
+                continue
+            }
+
+            let index = index + 1;
+            let wrong_direction = match req.direction {
+                HeadersDirection::Rising => next < prev,
+                HeadersDirection::Falling => next > prev,
+            };
+            if wrong_direction {
+                return Err(HeadersResponseError::WrongDirection { index })
+            }
+            return Err(HeadersResponseError::Gap { index, got: next, expected })
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the header numbers are strictly ascending, as expected in the response to a
+    /// [`HeadersDirection::Rising`] request.
+    pub fn ensure_ascending(&self) -> Result<(), OrderingError> {
+        self.ensure_ordered(HeadersDirection::Rising)
+    }
+
+    /// Checks that the header numbers are strictly descending, as expected in the response to a
+    /// [`HeadersDirection::Falling`] request.
+    pub fn ensure_descending(&self) -> Result<(), OrderingError> {
+        self.ensure_ordered(HeadersDirection::Falling)
+    }
+
+    /// Checks that the header numbers strictly follow the given direction.
+    fn ensure_ordered(&self, direction: HeadersDirection) -> Result<(), OrderingError> {
+        for (index, pair) in self.0.windows(2).enumerate() {
+            let (previous, number) = (pair[0].number, pair[1].number);
+            let ordered = match direction {
+                HeadersDirection::Rising => number > previous,
+                HeadersDirection::Falling => number < previous,
+            };
+            if !ordered {
+                return Err(OrderingError { index: index + 1, number, previous })
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that the headers form a hash chain starting at `anchor`, the hash of the requested
+    /// start block, as returned for a request without `skip`:
+    ///
+    /// - the first header hashes to `anchor`
+    /// - each header is the child of its predecessor, or its parent if `reverse` is set
+    ///
+    /// An empty response always matches, since peers may not have the requested headers.
+    pub fn verify_anchor(&self, anchor: B256, reverse: bool) -> Result<(), AnchorMismatch> {
+        let hashes = self.0.iter().map(Header::hash_slow).collect::<Vec<_>>();
+        let Some(&first) = hashes.first() else { return Ok(()) };
+        if first != anchor {
+            return Err(AnchorMismatch::Start(GotExpected { got: first, expected: anchor }))
+        }
+
+        for (index, pair) in self.0.windows(2).enumerate() {
+            let linked = if reverse {
+                pair[0].parent_hash == hashes[index + 1]
+            } else {
+                pair[1].parent_hash == hashes[index]
+            };
+            if !linked {
+                return Err(AnchorMismatch::Unlinked { index: index + 1 })
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Errors when a [`BlockHeaders`] response does not chain to the requested anchor, see
+/// [`BlockHeaders::verify_anchor`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AnchorMismatch {
+    /// The first header does not hash to the anchor.
+    #[error("first header does not match the anchor: {0}")]
+    Start(GotExpected<B256>),
+    /// A header is not linked to its predecessor by hash.
+    #[error("header at index {index} is not linked to its predecessor")]
+    Unlinked {
+        /// Index of the header in the response.
+        index: usize,
+    },
+}
+
+/// Error when the headers of a [`BlockHeaders`] response are not ordered, see
+/// [`BlockHeaders::ensure_ascending`] and [`BlockHeaders::ensure_descending`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("header at index {index} with number {number} is out of order after number {previous}")]
+pub struct OrderingError {
+    /// Index of the header in the response.
+    pub index: usize,
+    /// Number of the header.
+    pub number: u64,
+    /// Number of the preceding header.
+    pub previous: u64,
+}
+
+/// Mismatches between a [`BlockHeaders`] response and its [`GetBlockHeaders`] request, see
+/// [`BlockHeaders::matches_request`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum HeadersResponseError {
+    /// More headers than the requested limit were returned.
+    #[error("received {got} headers, but at most {limit} were requested")]
+    TooMany {
+        /// Number of headers in the response.
+        got: usize,
+        /// The requested limit.
+        limit: u64,
+    },
+    /// The first header is not the requested start block.
+    #[error("response starts at block {got}, which is not the requested start block")]
+    UnexpectedStart {
+        /// Number of the first header in the response.
+        got: u64,
+    },
+    /// A header goes in the opposite direction of the request.
+    #[error("header at index {index} goes in the wrong direction")]
+    WrongDirection {
+        /// Index of the header in the response.
+        index: usize,
+    },
+    /// A header is not `skip` blocks apart from its predecessor.
+    #[error("header at index {index} has number {got}, expected {expected:?}")]
+    Gap {
+        /// Index of the header in the response.
+        index: usize,
+        /// Number of the header.
+        got: u64,
+        /// The expected number, `None` if it would overflow.
+        expected: Option<u64>,
+    },
+}
+
 impl From<Vec<Header>> for BlockHeaders {
     fn from(headers: Vec<Header>) -> Self {
         Self(headers)
@@ -83,6 +252,18 @@ pub struct GetBlockBodies(
     pub Vec<B256>,
 );
 
+impl GetBlockBodies {
+    /// Removes duplicate hashes, keeping the first occurrence of each hash in place.
+    ///
+    /// Returns the number of removed hashes.
+    pub fn dedup(&mut self) -> usize {
+        let len = self.0.len();
+        let mut seen = HashSet::with_capacity(len);
+        self.0.retain(|hash| seen.insert(*hash));
+        len - self.0.len()
+    }
+}
+
 impl From<Vec<B256>> for GetBlockBodies {
     fn from(hashes: Vec<B256>) -> Self {
         Self(hashes)
@@ -99,19 +280,102 @@ pub struct BlockBodies(
     pub Vec<BlockBody>,
 );
 
+impl BlockBodies {
+    /// Creates a [`BlockBodies`] response from the bodies of the given sealed blocks.
+    pub fn from_blocks<I: IntoIterator<Item = SealedBlock>>(blocks: I) -> Self {
+        Self(blocks.into_iter().map(|block| block.split_header_body().1).collect())
+    }
+}
+
 impl From<Vec<BlockBody>> for BlockBodies {
     fn from(bodies: Vec<BlockBody>) -> Self {
         Self(bodies)
     }
 }
 
+/// Pairs separately downloaded headers and bodies into full blocks, by order.
+///
+/// Each body is validated against its header's transactions root and ommers hash while
+/// assembling, and the first mismatch is returned as an error.
+pub fn zip_blocks(headers: Vec<Header>, bodies: BlockBodies) -> Result<Vec<Block>, ZipError> {
+    if headers.len() != bodies.0.len() {
+        return Err(ZipError::CountMismatch { headers: headers.len(), bodies: bodies.0.len() })
+    }
+
+    headers
+        .into_iter()
+        .zip(bodies.0)
+        .enumerate()
+        .map(|(index, (header, body))| {
+            let transactions_root = body.calculate_tx_root();
+            if transactions_root != header.transactions_root {
+                return Err(ZipError::TransactionsRootMismatch {
+                    index,
+                    diff: GotExpected {
+                        got: transactions_root,
+                        expected: header.transactions_root,
+                    },
+                })
+            }
+
+            let ommers_hash = body.calculate_ommers_root();
+            if ommers_hash != header.ommers_hash {
+                return Err(ZipError::OmmersHashMismatch {
+                    index,
+                    diff: GotExpected { got: ommers_hash, expected: header.ommers_hash },
+                })
+            }
+
+            Ok(Block {
+                header,
+                body: body.transactions,
+                ommers: body.ommers,
+                withdrawals: body.withdrawals,
+                requests: body.requests,
+            })
+        })
+        .collect()
+}
+
+/// Errors when pairing headers with bodies, see [`zip_blocks`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ZipError {
+    /// The number of headers and bodies differ.
+    #[error("got {headers} headers but {bodies} bodies")]
+    CountMismatch {
+        /// Number of headers.
+        headers: usize,
+        /// Number of bodies.
+        bodies: usize,
+    },
+    /// A body's transactions don't match its header's transactions root.
+    #[error("body at index {index} has mismatched transactions root: {diff}")]
+    TransactionsRootMismatch {
+        /// Index of the block.
+        index: usize,
+        /// The computed and the header's transactions root.
+        diff: GotExpected<B256>,
+    },
+    /// A body's ommers don't match its header's ommers hash.
+    #[error("body at index {index} has mismatched ommers hash: {diff}")]
+    OmmersHashMismatch {
+        /// Index of the block.
+        index: usize,
+        /// The computed and the header's ommers hash.
+        diff: GotExpected<B256>,
+    },
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{message::RequestPair, BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders};
+    use crate::{
+        message::RequestPair, zip_blocks, AnchorMismatch, BlockBodies, BlockHeaders,
+        GetBlockBodies, GetBlockHeaders, HeadersResponseError, OrderingError, ZipError,
+    };
     use alloy_rlp::{Decodable, Encodable};
     use reth_primitives::{
-        hex, BlockHashOrNumber, Header, HeadersDirection, Signature, Transaction,
-        TransactionSigned, TxKind, TxLegacy, U256,
+        hex, BlockHashOrNumber, GotExpected, Header, HeadersDirection, SealedBlock, Signature,
+        Transaction, TransactionSigned, TxKind, TxLegacy, Withdrawals, B256, U256,
     };
     use std::str::FromStr;
 
@@ -497,4 +761,221 @@ mod tests {
         let result = RequestPair::decode(&mut &data[..]).unwrap();
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn block_bodies_from_blocks_roundtrip() {
+        let bodies = vec![
+            BlockBody {
+                transactions: vec![],
+                ommers: vec![Header { number: 1, ..Default::default() }],
+                withdrawals: None,
+                requests: None,
+            },
+            BlockBody {
+                transactions: vec![],
+                ommers: vec![],
+                withdrawals: Some(Withdrawals::default()),
+                requests: None,
+            },
+        ];
+        let blocks = bodies
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(number, body)| {
+                let header = Header { number: number as u64, ..Default::default() }.seal_slow();
+                SealedBlock::new(header, body)
+            })
+            .collect::<Vec<_>>();
+
+        let response = BlockBodies::from_blocks(blocks);
+        assert_eq!(response, BlockBodies(bodies));
+
+        let mut encoded = vec![];
+        response.encode(&mut encoded);
+        assert_eq!(BlockBodies::decode(&mut &encoded[..]).unwrap(), response);
+    }
+
+    fn headers(numbers: impl IntoIterator<Item = u64>) -> BlockHeaders {
+        BlockHeaders(
+            numbers.into_iter().map(|number| Header { number, ..Default::default() }).collect(),
+        )
+    }
+
+    #[test]
+    fn block_headers_match_request() {
+        let req = GetBlockHeaders {
+            start_block: BlockHashOrNumber::Number(10),
+            limit: 3,
+            skip: 1,
+            direction: HeadersDirection::Rising,
+        };
+        assert_eq!(headers([10, 12, 14]).matches_request(&req), Ok(()));
+        assert_eq!(headers([10, 12]).matches_request(&req), Ok(()));
+        assert_eq!(headers([]).matches_request(&req), Ok(()));
+
+        let falling = GetBlockHeaders { direction: HeadersDirection::Falling, ..req };
+        assert_eq!(headers([10, 8, 6]).matches_request(&falling), Ok(()));
+
+        let by_hash = headers([10, 12]);
+        let req_by_hash = GetBlockHeaders {
+            start_block: BlockHashOrNumber::Hash(by_hash.0[0].hash_slow()),
+            ..req
+        };
+        assert_eq!(by_hash.matches_request(&req_by_hash), Ok(()));
+    }
+
+    #[test]
+    fn block_headers_mismatch_request() {
+        let req = GetBlockHeaders {
+            start_block: BlockHashOrNumber::Number(10),
+            limit: 3,
+            skip: 1,
+            direction: HeadersDirection::Rising,
+        };
+        assert_eq!(
+            headers([10, 11, 12]).matches_request(&req),
+            Err(HeadersResponseError::Gap { index: 1, got: 11, expected: Some(12) })
+        );
+        assert_eq!(
+            headers([10, 8]).matches_request(&req),
+            Err(HeadersResponseError::WrongDirection { index: 1 })
+        );
+        assert_eq!(
+            headers([10, 12, 14, 16]).matches_request(&req),
+            Err(HeadersResponseError::TooMany { got: 4, limit: 3 })
+        );
+        assert_eq!(
+            headers([11, 13]).matches_request(&req),
+            Err(HeadersResponseError::UnexpectedStart { got: 11 })
+        );
+    }
+
+    #[test]
+    fn zip_blocks_validates_bodies() {
+        let tx = |nonce| {
+            TransactionSigned::from_transaction_and_signature(
+                Transaction::Legacy(TxLegacy { nonce, ..Default::default() }),
+                Signature::default(),
+            )
+        };
+        let body = |nonce| BlockBody { transactions: vec![tx(nonce)], ..Default::default() };
+        let header = |body: &BlockBody| Header {
+            transactions_root: body.calculate_tx_root(),
+            ommers_hash: body.calculate_ommers_root(),
+            ..Default::default()
+        };
+        let (first, second) = (body(0), body(1));
+        let headers = vec![header(&first), header(&second)];
+
+        let blocks =
+            zip_blocks(headers.clone(), BlockBodies(vec![first.clone(), second.clone()])).unwrap();
+        assert_eq!(
+            blocks,
+            vec![first.create_block(headers[0].clone()), second.create_block(headers[1].clone())]
+        );
+
+        // swapped bodies
+        assert_eq!(
+            zip_blocks(headers.clone(), BlockBodies(vec![second.clone(), first.clone()])),
+            Err(ZipError::TransactionsRootMismatch {
+                index: 0,
+                diff: GotExpected {
+                    got: second.calculate_tx_root(),
+                    expected: headers[0].transactions_root
+                }
+            })
+        );
+
+        // ommers not committed to by the header
+        let with_ommers = BlockBody { ommers: vec![Header::default()], ..first };
+        assert_eq!(
+            zip_blocks(headers.clone(), BlockBodies(vec![with_ommers.clone(), second.clone()])),
+            Err(ZipError::OmmersHashMismatch {
+                index: 0,
+                diff: GotExpected {
+                    got: with_ommers.calculate_ommers_root(),
+                    expected: headers[0].ommers_hash
+                }
+            })
+        );
+
+        assert_eq!(
+            zip_blocks(headers, BlockBodies(vec![second])),
+            Err(ZipError::CountMismatch { headers: 2, bodies: 1 })
+        );
+    }
+
+    #[test]
+    fn verify_anchor() {
+        let mut chain = Vec::new();
+        let mut parent_hash = B256::ZERO;
+        for number in 0..4 {
+            let header = Header { number, parent_hash, ..Default::default() };
+            parent_hash = header.hash_slow();
+            chain.push(header);
+        }
+        let anchor = chain[0].hash_slow();
+
+        let rising = BlockHeaders(chain.clone());
+        assert_eq!(rising.verify_anchor(anchor, false), Ok(()));
+        assert_eq!(rising.verify_anchor(anchor, true), Err(AnchorMismatch::Unlinked { index: 1 }));
+
+        let falling = BlockHeaders(chain.iter().rev().cloned().collect());
+        assert_eq!(falling.verify_anchor(chain[3].hash_slow(), true), Ok(()));
+
+        // headers from another chain
+        let wrong = B256::with_last_byte(1);
+        assert_eq!(
+            rising.verify_anchor(wrong, false),
+            Err(AnchorMismatch::Start(GotExpected { got: anchor, expected: wrong }))
+        );
+
+        let mut broken = chain;
+        broken[2].parent_hash = wrong;
+        assert_eq!(
+            BlockHeaders(broken).verify_anchor(anchor, false),
+            Err(AnchorMismatch::Unlinked { index: 2 })
+        );
+
+        assert_eq!(BlockHeaders::default().verify_anchor(wrong, false), Ok(()));
+    }
+
+    #[test]
+    fn headers_ordering() {
+        let headers = |numbers: &[u64]| {
+            BlockHeaders(
+                numbers.iter().map(|&number| Header { number, ..Default::default() }).collect(),
+            )
+        };
+
+        assert_eq!(headers(&[1, 2, 5]).ensure_ascending(), Ok(()));
+        assert_eq!(headers(&[5, 2, 1]).ensure_descending(), Ok(()));
+        assert_eq!(headers(&[]).ensure_ascending(), Ok(()));
+        assert_eq!(headers(&[7]).ensure_descending(), Ok(()));
+
+        let jumbled = headers(&[3, 5, 4, 6]);
+        assert_eq!(
+            jumbled.ensure_ascending(),
+            Err(OrderingError { index: 2, number: 4, previous: 5 })
+        );
+        assert_eq!(
+            jumbled.ensure_descending(),
+            Err(OrderingError { index: 1, number: 5, previous: 3 })
+        );
+        assert_eq!(
+            headers(&[3, 3]).ensure_ascending(),
+            Err(OrderingError { index: 1, number: 3, previous: 3 })
+        );
+    }
+
+    #[test]
+    fn get_block_bodies_dedup() {
+        let hash = B256::with_last_byte;
+        let mut request =
+            GetBlockBodies(vec![hash(1), hash(2), hash(1), hash(3), hash(2), hash(1)]);
+        assert_eq!(request.dedup(), 3);
+        assert_eq!(request, GetBlockBodies(vec![hash(1), hash(2), hash(3)]));
+        assert_eq!(request.dedup(), 0);
+    }
 }
@@ -6,9 +6,11 @@ use alloy_rlp::{
 };
 
 use derive_more::{Constructor, Deref, DerefMut, From, IntoIterator};
+use reth_chainspec::ChainSpec;
 use reth_codecs_derive::derive_arbitrary;
 use reth_primitives::{
-    Block, Bytes, PooledTransactionsElement, TransactionSigned, TxHash, B256, U128,
+    Block, BlockNumber, Bytes, Hardfork, PooledTransactionsElement, TransactionSigned, TxHash,
+    TxType, B256, U128, U256,
 };
 
 use std::{
@@ -84,6 +86,58 @@ pub struct NewBlock {
     pub td: U128,
 }
 
+impl NewBlock {
+    /// Validates that the announced total difficulty is plausible for the embedded block.
+    ///
+    /// The total difficulty includes the difficulty of the block itself, so it can never be lower
+    /// than that. Once the merge is reached (at Bedrock for OP chains) the total difficulty stops
+    /// growing, so it can never be lower than the terminal total difficulty either.
+    pub fn validate_td(&self, chain_spec: &ChainSpec) -> Result<(), TdError> {
+        let td = U256::from(self.td);
+        if td < self.block.difficulty {
+            return Err(TdError::BelowBlockDifficulty { td, difficulty: self.block.difficulty })
+        }
+
+        let paris = chain_spec.fork(Hardfork::Paris);
+        if paris.active_at_block(self.block.number) {
+            if let Some(ttd) = paris.ttd() {
+                if td < ttd {
+                    return Err(TdError::BelowTerminalTotalDifficulty {
+                        number: self.block.number,
+                        td,
+                        ttd,
+                    })
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Error returned by [`NewBlock::validate_td`] for an implausible total difficulty.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum TdError {
+    /// The total difficulty is lower than the difficulty of the block itself.
+    #[error("total difficulty {td} is lower than the block difficulty {difficulty}")]
+    BelowBlockDifficulty {
+        /// The announced total difficulty.
+        td: U256,
+        /// The difficulty of the block.
+        difficulty: U256,
+    },
+    /// The total difficulty of a post-merge block is lower than the terminal total difficulty.
+    #[error("total difficulty {td} of post-merge block {number} is lower than the terminal total difficulty {ttd}")]
+    BelowTerminalTotalDifficulty {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The announced total difficulty.
+        td: U256,
+        /// The terminal total difficulty of the chain.
+        ttd: U256,
+    },
+}
+
 /// This informs peers of transactions that have appeared on the network and are not yet included
 /// in a block.
 #[derive_arbitrary(rlp, 10)]
@@ -99,6 +153,105 @@ impl Transactions {
     pub fn has_eip4844(&self) -> bool {
         self.0.iter().any(|tx| tx.is_eip4844())
     }
+
+    /// Returns a copy of the message without the transactions of the given type, e.g. for peers
+    /// that don't want blob transactions relayed.
+    pub fn without_type(&self, ty: u8) -> Self {
+        Self(self.0.iter().filter(|tx| u8::from(tx.tx_type()) != ty).cloned().collect())
+    }
+
+    /// Retains only the transactions whose type is in `allowed`.
+    pub fn retain_types(&mut self, allowed: &[u8]) {
+        self.0.retain(|tx| allowed.contains(&u8::from(tx.tx_type())));
+    }
+
+    /// Splits the transactions into messages whose RLP encoding is at most `max_bytes` long,
+    /// keeping the order of the transactions.
+    ///
+    /// Transactions are never split, a transaction that exceeds `max_bytes` on its own is sent
+    /// alone.
+    pub fn into_chunks(self, max_bytes: usize) -> impl Iterator<Item = Self> {
+        let mut txs = self.0.into_iter().peekable();
+        std::iter::from_fn(move || {
+            let mut chunk = Vec::new();
+            let mut payload_len = 0;
+            while let Some(tx) = txs.next_if(|tx| {
+                let len = payload_len + tx.length();
+                chunk.is_empty() ||
+                    alloy_rlp::Header { list: true, payload_length: len }.length() + len <=
+                        max_bytes
+            }) {
+                payload_len += tx.length();
+                chunk.push(tx);
+            }
+            (!chunk.is_empty()).then_some(Self(chunk))
+        })
+    }
+
+    /// Decodes a [`Transactions`] message, handling transactions of unknown type according to the
+    /// given [`UnknownTxPolicy`].
+    ///
+    /// Returns the decoded transactions and the number of transactions that were skipped.
+    pub fn decode_with_policy(
+        buf: &mut &[u8],
+        policy: UnknownTxPolicy,
+    ) -> alloy_rlp::Result<(Self, usize)> {
+        let header = alloy_rlp::Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        if buf.len() < header.payload_length {
+            return Err(alloy_rlp::Error::InputTooShort)
+        }
+
+        let (mut payload, rest) = buf.split_at(header.payload_length);
+        let mut txs = Vec::new();
+        let mut skipped = 0;
+        while !payload.is_empty() {
+            if policy == UnknownTxPolicy::Skip {
+                if let Some(len) = unknown_typed_tx_len(payload)? {
+                    payload = &payload[len..];
+                    skipped += 1;
+                    continue
+                }
+            }
+            txs.push(TransactionSigned::decode(&mut payload)?);
+        }
+
+        *buf = rest;
+        Ok((Self(txs), skipped))
+    }
+}
+
+/// Returns the encoded length of the next transaction in `buf` if it is a typed transaction of an
+/// unknown type.
+fn unknown_typed_tx_len(buf: &[u8]) -> alloy_rlp::Result<Option<usize>> {
+    let mut payload = buf;
+    let header = alloy_rlp::Header::decode(&mut payload)?;
+    if header.list {
+        // legacy transaction
+        return Ok(None)
+    }
+    if payload.len() < header.payload_length {
+        return Err(alloy_rlp::Error::InputTooShort)
+    }
+
+    let ty = *payload[..header.payload_length].first().ok_or(alloy_rlp::Error::InputTooShort)?;
+    if TxType::try_from(ty).is_ok() {
+        return Ok(None)
+    }
+    Ok(Some(buf.len() - payload.len() + header.payload_length))
+}
+
+/// How to handle transactions of unknown type when decoding [`Transactions`], see
+/// [`Transactions::decode_with_policy`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum UnknownTxPolicy {
+    /// Fail decoding the whole message.
+    #[default]
+    Reject,
+    /// Drop transactions of unknown type.
+    Skip,
 }
 
 impl From<Vec<TransactionSigned>> for Transactions {
@@ -153,7 +306,7 @@ impl NewPooledTransactionHashes {
                 matches!(version, EthVersion::Eth67 | EthVersion::Eth66)
             }
             Self::Eth68(_) => {
-                matches!(version, EthVersion::Eth68)
+                matches!(version, EthVersion::Eth68 | EthVersion::Eth69)
             }
         }
     }
@@ -373,6 +526,41 @@ impl NewPooledTransactionHashes68 {
     pub fn metadata_iter(&self) -> impl Iterator<Item = (&B256, (u8, usize))> {
         self.hashes.iter().zip(self.types.iter().copied().zip(self.sizes.iter().copied()))
     }
+
+    /// Splits the announcement into announcements whose RLP encoding is at most `max_bytes` long,
+    /// keeping each hash together with its type and size.
+    ///
+    /// An entry that exceeds `max_bytes` on its own is announced alone.
+    pub fn split_within(self, max_bytes: usize) -> Vec<Self> {
+        // upper bound of the encoded length of `count` entries whose sizes take `sizes_len` bytes
+        let encoded_len = |count: usize, sizes_len: usize| {
+            let string =
+                |len| alloy_rlp::Header { list: false, payload_length: len }.length() + len;
+            let list = |len| alloy_rlp::Header { list: true, payload_length: len }.length() + len;
+            // each hash is encoded as a 32 byte string
+            list(string(count) + list(sizes_len) + list(count * (1 + 32)))
+        };
+
+        let mut messages = Vec::new();
+        let mut current = Self::default();
+        let mut sizes_len = 0;
+        for ((ty, size), hash) in self.types.into_iter().zip(self.sizes).zip(self.hashes) {
+            if !current.hashes.is_empty() &&
+                encoded_len(current.hashes.len() + 1, sizes_len + size.length()) > max_bytes
+            {
+                messages.push(mem::take(&mut current));
+                sizes_len = 0;
+            }
+            current.types.push(ty);
+            current.sizes.push(size);
+            current.hashes.push(hash);
+            sizes_len += size.length();
+        }
+        if !current.hashes.is_empty() {
+            messages.push(current);
+        }
+        messages
+    }
 }
 
 impl Encodable for NewPooledTransactionHashes68 {
@@ -738,7 +926,11 @@ impl FromIterator<(TxHash, Eth68TxMetadata)> for RequestTxHashes {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use reth_primitives::{b256, hex};
+    use reth_chainspec::ChainSpecBuilder;
+    use reth_primitives::{
+        b256, hex, ForkCondition, Header, Signature, Transaction, TxEip1559, TxEip4844, TxLegacy,
+        EIP1559_TX_TYPE_ID, EIP4844_TX_TYPE_ID, LEGACY_TX_TYPE_ID,
+    };
     use std::str::FromStr;
 
     /// Takes as input a struct / encoded hex message pair, ensuring that we encode to the exact hex
@@ -947,4 +1139,155 @@ mod tests {
         assert_eq!(0, hashes.len());
         assert_eq!(5, rest.len());
     }
+
+    #[test]
+    fn new_block_validate_td() {
+        let chain_spec = ChainSpecBuilder::mainnet()
+            .with_fork(
+                Hardfork::Paris,
+                ForkCondition::TTD { fork_block: Some(10), total_difficulty: U256::from(1_000) },
+            )
+            .build();
+
+        let new_block = |number: u64, difficulty: u64, td: u64| NewBlock {
+            block: Block {
+                header: Header { number, difficulty: U256::from(difficulty), ..Default::default() },
+                ..Default::default()
+            },
+            td: U128::from(td),
+        };
+
+        assert_eq!(new_block(5, 100, 500).validate_td(&chain_spec), Ok(()));
+        assert_eq!(new_block(10, 0, 1_000).validate_td(&chain_spec), Ok(()));
+
+        assert_eq!(
+            new_block(5, 100, 50).validate_td(&chain_spec),
+            Err(TdError::BelowBlockDifficulty { td: U256::from(50), difficulty: U256::from(100) })
+        );
+        assert_eq!(
+            new_block(11, 0, 999).validate_td(&chain_spec),
+            Err(TdError::BelowTerminalTotalDifficulty {
+                number: 11,
+                td: U256::from(999),
+                ttd: U256::from(1_000)
+            })
+        );
+    }
+
+    /// Returns an encoded [`Transactions`] message with a legacy transaction followed by a
+    /// transaction of unknown type `0x42`.
+    fn transactions_with_unknown_type() -> (TransactionSigned, Vec<u8>) {
+        let tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy { nonce: 1, gas_limit: 21_000, ..Default::default() }),
+            Signature::default(),
+        );
+        let unknown = Bytes::from_static(&[0x42, 0xc0]);
+
+        let mut payload = Vec::new();
+        tx.encode(&mut payload);
+        unknown.encode(&mut payload);
+        let mut buf = Vec::new();
+        alloy_rlp::Header { list: true, payload_length: payload.len() }.encode(&mut buf);
+        buf.extend_from_slice(&payload);
+        (tx, buf)
+    }
+
+    #[test]
+    fn decode_transactions_reject_unknown_type() {
+        let (_, buf) = transactions_with_unknown_type();
+        assert!(Transactions::decode_with_policy(&mut &buf[..], UnknownTxPolicy::Reject).is_err());
+        assert!(Transactions::decode(&mut &buf[..]).is_err());
+    }
+
+    #[test]
+    fn decode_transactions_skip_unknown_type() {
+        let (tx, buf) = transactions_with_unknown_type();
+        let mut slice = &buf[..];
+        let (txs, skipped) =
+            Transactions::decode_with_policy(&mut slice, UnknownTxPolicy::Skip).unwrap();
+        assert_eq!(txs, Transactions(vec![tx]));
+        assert_eq!(skipped, 1);
+        assert!(slice.is_empty());
+    }
+
+    #[test]
+    fn split_new_pooled_transaction_hashes_68() {
+        let msg = NewPooledTransactionHashes68 {
+            types: (0..100).map(|i| (i % 3) as u8).collect(),
+            sizes: (0..100).map(|i| i * 1_000).collect(),
+            hashes: (0..100).map(|i| B256::with_last_byte(i as u8)).collect(),
+        };
+
+        let max_bytes = 1_000;
+        let messages = msg.clone().split_within(max_bytes);
+        assert!(messages.len() > 1);
+        for message in &messages {
+            assert!(message.length() <= max_bytes);
+            assert_eq!(message.types.len(), message.hashes.len());
+            assert_eq!(message.sizes.len(), message.hashes.len());
+        }
+
+        let mut joined = NewPooledTransactionHashes68::default();
+        for message in messages {
+            joined.types.extend(message.types);
+            joined.sizes.extend(message.sizes);
+            joined.hashes.extend(message.hashes);
+        }
+        assert_eq!(joined, msg);
+
+        assert_eq!(msg.clone().split_within(usize::MAX), vec![msg]);
+    }
+
+    #[test]
+    fn transactions_into_chunks() {
+        let tx = |input: usize| {
+            TransactionSigned::from_transaction_and_signature(
+                Transaction::Legacy(TxLegacy {
+                    input: vec![0xaa; input].into(),
+                    ..Default::default()
+                }),
+                Signature::default(),
+            )
+        };
+        let txs = Transactions(vec![tx(100), tx(100), tx(2_000), tx(100), tx(100), tx(100)]);
+
+        let max_bytes = 500;
+        let chunks = txs.clone().into_chunks(max_bytes).collect::<Vec<_>>();
+        assert_eq!(chunks.iter().map(|chunk| chunk.0.len()).collect::<Vec<_>>(), vec![2, 1, 3]);
+        assert_eq!(chunks[1].0, vec![tx(2_000)]);
+        assert!(chunks[1].length() > max_bytes);
+        for chunk in [&chunks[0], &chunks[2]] {
+            assert!(chunk.length() <= max_bytes);
+        }
+        assert_eq!(chunks.into_iter().flat_map(|chunk| chunk.0).collect::<Vec<_>>(), txs.0);
+
+        assert_eq!(Transactions::default().into_chunks(max_bytes).count(), 0);
+    }
+
+    #[test]
+    fn transactions_filter_by_type() {
+        let tx = |transaction| {
+            TransactionSigned::from_transaction_and_signature(transaction, Signature::default())
+        };
+        let legacy = tx(Transaction::Legacy(TxLegacy::default()));
+        let eip1559 = tx(Transaction::Eip1559(TxEip1559::default()));
+        let eip4844 = tx(Transaction::Eip4844(TxEip4844::default()));
+        let mut txs = Transactions(vec![
+            legacy.clone(),
+            eip4844.clone(),
+            eip1559.clone(),
+            eip4844,
+            legacy.clone(),
+        ]);
+
+        let without_blobs = txs.without_type(EIP4844_TX_TYPE_ID);
+        assert!(!without_blobs.has_eip4844());
+        assert_eq!(without_blobs.0, vec![legacy.clone(), eip1559.clone(), legacy]);
+        assert_eq!(txs.0.len(), 5);
+
+        txs.retain_types(&[LEGACY_TX_TYPE_ID, EIP1559_TX_TYPE_ID]);
+        assert_eq!(txs, without_blobs);
+        txs.retain_types(&[EIP1559_TX_TYPE_ID]);
+        assert_eq!(txs.0, vec![eip1559]);
+    }
 }
@@ -10,13 +10,15 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 mod status;
-pub use status::{Status, StatusBuilder};
+#[cfg(feature = "serde")]
+pub use status::FromJsonError;
+pub use status::{BlockRangeUpdate, Status, StatusBuilder, StatusDiff};
 
 pub mod version;
 pub use version::EthVersion;
 
 pub mod message;
-pub use message::{EthMessage, EthMessageID, ProtocolMessage};
+pub use message::{EthMessage, EthMessageID, MessageTooLarge, ProtocolMessage};
 
 pub mod blocks;
 pub use blocks::*;
@@ -1,4 +1,5 @@
-//! Implements Ethereum wire protocol for versions 66, 67, and 68.
+//! Implements Ethereum wire protocol for versions 66, 67, and 68, and the `BlockRangeUpdate`
+//! message of version 69.
 //! Defines structs/enums for messages, request-response pairs, and broadcasts.
 //! Handles compatibility with [`EthVersion`].
 //!
@@ -7,9 +8,10 @@
 //! Reference: [Ethereum Wire Protocol](https://github.com/ethereum/wiki/wiki/Ethereum-Wire-Protocol).
 
 use super::{
-    broadcast::NewBlockHashes, BlockBodies, BlockHeaders, GetBlockBodies, GetBlockHeaders,
-    GetNodeData, GetPooledTransactions, GetReceipts, NewBlock, NewPooledTransactionHashes66,
-    NewPooledTransactionHashes68, NodeData, PooledTransactions, Receipts, Status, Transactions,
+    broadcast::NewBlockHashes, BlockBodies, BlockHeaders, BlockRangeUpdate, GetBlockBodies,
+    GetBlockHeaders, GetNodeData, GetPooledTransactions, GetReceipts, NewBlock,
+    NewPooledTransactionHashes66, NewPooledTransactionHashes68, NodeData, PooledTransactions,
+    Receipts, Status, Transactions,
 };
 use crate::{EthVersion, SharedTransactions};
 
@@ -34,6 +36,39 @@ pub enum MessageError {
     RlpError(#[from] alloy_rlp::Error),
 }
 
+/// Error returned by [`EthMessage::encode_checked`] when the encoded message would exceed the
+/// given size limit.
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("encoded message is {size} bytes, exceeding the limit of {max_bytes} bytes")]
+pub struct MessageTooLarge {
+    /// The encoded length of the message.
+    pub size: usize,
+    /// The maximum allowed length.
+    pub max_bytes: usize,
+}
+
+impl MessageError {
+    /// Returns the [`DecodeErrorKind`] of the error.
+    pub const fn kind(&self) -> DecodeErrorKind {
+        match self {
+            Self::Invalid(..) => DecodeErrorKind::UnsupportedVersion,
+            Self::RlpError(alloy_rlp::Error::InputTooShort) => DecodeErrorKind::Truncated,
+            Self::RlpError(_) => DecodeErrorKind::Malformed,
+        }
+    }
+}
+
+/// Classifies why a message failed to decode, so the peer can be penalized accordingly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeErrorKind {
+    /// The message is invalid and can't have been produced by a correct peer.
+    Malformed,
+    /// The message ended early, which may be benign.
+    Truncated,
+    /// The message is not part of the negotiated protocol version.
+    UnsupportedVersion,
+}
+
 /// An `eth` protocol message, containing a message ID and payload.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -113,6 +148,12 @@ impl ProtocolMessage {
                 let request_pair = RequestPair::<Receipts>::decode(buf)?;
                 EthMessage::Receipts(request_pair)
             }
+            EthMessageID::BlockRangeUpdate => {
+                if !version.supports_block_range_update() {
+                    return Err(MessageError::Invalid(version, EthMessageID::BlockRangeUpdate))
+                }
+                EthMessage::BlockRangeUpdate(BlockRangeUpdate::decode(buf)?)
+            }
         };
         Ok(Self { message_type, message })
     }
@@ -181,6 +222,8 @@ impl From<EthBroadcastMessage> for ProtocolBroadcastMessage {
 /// The `eth/68` changes only `NewPooledTransactionHashes` to include `types` and `sized`. For
 /// it, `NewPooledTransactionHashes` is renamed as [`NewPooledTransactionHashes66`] and
 /// [`NewPooledTransactionHashes68`] is defined.
+///
+/// Of `eth/69`, only the [`BlockRangeUpdate`] message is supported.
 #[derive(Clone, Debug, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum EthMessage {
@@ -217,6 +260,8 @@ pub enum EthMessage {
     GetReceipts(RequestPair<GetReceipts>),
     /// Represents a Receipts request-response pair.
     Receipts(RequestPair<Receipts>),
+    /// Represents a `BlockRangeUpdate` message broadcast to the network.
+    BlockRangeUpdate(BlockRangeUpdate),
 }
 
 impl EthMessage {
@@ -240,6 +285,108 @@ impl EthMessage {
             Self::NodeData(_) => EthMessageID::NodeData,
             Self::GetReceipts(_) => EthMessageID::GetReceipts,
             Self::Receipts(_) => EthMessageID::Receipts,
+            Self::BlockRangeUpdate(_) => EthMessageID::BlockRangeUpdate,
+        }
+    }
+
+    /// Encodes the message prefixed with its message id, as [`ProtocolMessage`] does, into `out`
+    /// only if the encoded length, including the message id, does not exceed `max_bytes`.
+    ///
+    /// The length is computed up front, so nothing is written to `out` if the message is too
+    /// large.
+    pub fn encode_checked(
+        &self,
+        out: &mut dyn BufMut,
+        max_bytes: usize,
+    ) -> Result<(), MessageTooLarge> {
+        let message_id = self.message_id();
+        let size = message_id.length() + self.length();
+        if size > max_bytes {
+            return Err(MessageTooLarge { size, max_bytes })
+        }
+        message_id.encode(out);
+        self.encode(out);
+        Ok(())
+    }
+
+    /// Returns a one-line summary of the message, e.g. `Transactions(count=14, bytes=9021)`,
+    /// suitable for logging instead of the full payload.
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Status(status) => {
+                format!(
+                    "Status(version={}, chain={}, head={})",
+                    status.version, status.chain, status.blockhash
+                )
+            }
+            Self::NewBlockHashes(hashes) => format!("NewBlockHashes(count={})", hashes.0.len()),
+            Self::NewBlock(block) => format!(
+                "NewBlock(number={}, txs={}, bytes={})",
+                block.block.number,
+                block.block.body.len(),
+                block.length()
+            ),
+            Self::Transactions(txs) => {
+                format!("Transactions(count={}, bytes={})", txs.0.len(), txs.length())
+            }
+            Self::NewPooledTransactionHashes66(hashes) => {
+                format!("NewPooledTransactionHashes66(count={})", hashes.0.len())
+            }
+            Self::NewPooledTransactionHashes68(hashes) => {
+                format!("NewPooledTransactionHashes68(count={})", hashes.hashes.len())
+            }
+            Self::GetBlockHeaders(req) => format!(
+                "GetBlockHeaders(id={}, start={}, limit={}, skip={}, direction={:?})",
+                req.request_id,
+                req.message.start_block,
+                req.message.limit,
+                req.message.skip,
+                req.message.direction
+            ),
+            Self::BlockHeaders(res) => {
+                format!("BlockHeaders(id={}, count={})", res.request_id, res.message.0.len())
+            }
+            Self::GetBlockBodies(req) => {
+                format!("GetBlockBodies(id={}, count={})", req.request_id, req.message.0.len())
+            }
+            Self::BlockBodies(res) => format!(
+                "BlockBodies(id={}, count={}, bytes={})",
+                res.request_id,
+                res.message.0.len(),
+                res.message.length()
+            ),
+            Self::GetPooledTransactions(req) => format!(
+                "GetPooledTransactions(id={}, count={})",
+                req.request_id,
+                req.message.0.len()
+            ),
+            Self::PooledTransactions(res) => format!(
+                "PooledTransactions(id={}, count={}, bytes={})",
+                res.request_id,
+                res.message.0.len(),
+                res.message.length()
+            ),
+            Self::GetNodeData(req) => {
+                format!("GetNodeData(id={}, count={})", req.request_id, req.message.0.len())
+            }
+            Self::NodeData(res) => format!(
+                "NodeData(id={}, count={}, bytes={})",
+                res.request_id,
+                res.message.0.len(),
+                res.message.length()
+            ),
+            Self::GetReceipts(req) => {
+                format!("GetReceipts(id={}, count={})", req.request_id, req.message.0.len())
+            }
+            Self::Receipts(res) => format!(
+                "Receipts(id={}, blocks={}, bytes={})",
+                res.request_id,
+                res.message.0.len(),
+                res.message.length()
+            ),
+            Self::BlockRangeUpdate(update) => {
+                format!("BlockRangeUpdate(earliest={}, latest={})", update.earliest, update.latest)
+            }
         }
     }
 }
@@ -263,6 +410,7 @@ impl Encodable for EthMessage {
             Self::NodeData(data) => data.encode(out),
             Self::GetReceipts(request) => request.encode(out),
             Self::Receipts(receipts) => receipts.encode(out),
+            Self::BlockRangeUpdate(update) => update.encode(out),
         }
     }
     fn length(&self) -> usize {
@@ -283,6 +431,7 @@ impl Encodable for EthMessage {
             Self::NodeData(data) => data.length(),
             Self::GetReceipts(request) => request.length(),
             Self::Receipts(receipts) => receipts.length(),
+            Self::BlockRangeUpdate(update) => update.length(),
         }
     }
 }
@@ -365,27 +514,55 @@ pub enum EthMessageID {
     GetReceipts = 0x0f,
     /// Represents receipts.
     Receipts = 0x10,
+    /// Block range update message, introduced in eth/69.
+    BlockRangeUpdate = 0x11,
 }
 
 impl EthMessageID {
     /// Returns the max value.
     pub const fn max() -> u8 {
-        Self::Receipts as u8
+        Self::BlockRangeUpdate as u8
     }
-}
 
-impl Encodable for EthMessageID {
-    fn encode(&self, out: &mut dyn BufMut) {
-        out.put_u8(*self as u8);
+    /// Returns the max value of the given protocol version.
+    pub const fn max_for_version(version: EthVersion) -> u8 {
+        if version.supports_block_range_update() {
+            Self::BlockRangeUpdate as u8
+        } else {
+            Self::Receipts as u8
+        }
     }
-    fn length(&self) -> usize {
-        1
+
+    /// Returns all message IDs, in ascending order.
+    pub const fn all() -> &'static [Self] {
+        &[
+            Self::Status,
+            Self::NewBlockHashes,
+            Self::Transactions,
+            Self::GetBlockHeaders,
+            Self::BlockHeaders,
+            Self::GetBlockBodies,
+            Self::BlockBodies,
+            Self::NewBlock,
+            Self::NewPooledTransactionHashes,
+            Self::GetPooledTransactions,
+            Self::PooledTransactions,
+            Self::GetNodeData,
+            Self::NodeData,
+            Self::GetReceipts,
+            Self::Receipts,
+            Self::BlockRangeUpdate,
+        ]
     }
-}
 
-impl Decodable for EthMessageID {
-    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
-        let id = match buf.first().ok_or(alloy_rlp::Error::InputTooShort)? {
+    /// Returns the message ID as its wire byte.
+    pub const fn as_u8(&self) -> u8 {
+        *self as u8
+    }
+
+    /// Returns the message ID for the given wire byte, if it is a known message ID.
+    pub const fn from_u8(id: u8) -> Option<Self> {
+        Some(match id {
             0x00 => Self::Status,
             0x01 => Self::NewBlockHashes,
             0x02 => Self::Transactions,
@@ -401,8 +578,25 @@ impl Decodable for EthMessageID {
             0x0e => Self::NodeData,
             0x0f => Self::GetReceipts,
             0x10 => Self::Receipts,
-            _ => return Err(alloy_rlp::Error::Custom("Invalid message ID")),
-        };
+            0x11 => Self::BlockRangeUpdate,
+            _ => return None,
+        })
+    }
+}
+
+impl Encodable for EthMessageID {
+    fn encode(&self, out: &mut dyn BufMut) {
+        out.put_u8(*self as u8);
+    }
+    fn length(&self) -> usize {
+        1
+    }
+}
+
+impl Decodable for EthMessageID {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let id = Self::from_u8(*buf.first().ok_or(alloy_rlp::Error::InputTooShort)?)
+            .ok_or(alloy_rlp::Error::Custom("Invalid message ID"))?;
         buf.advance(1);
         Ok(id)
     }
@@ -412,24 +606,7 @@ impl TryFrom<usize> for EthMessageID {
     type Error = &'static str;
 
     fn try_from(value: usize) -> Result<Self, Self::Error> {
-        match value {
-            0x00 => Ok(Self::Status),
-            0x01 => Ok(Self::NewBlockHashes),
-            0x02 => Ok(Self::Transactions),
-            0x03 => Ok(Self::GetBlockHeaders),
-            0x04 => Ok(Self::BlockHeaders),
-            0x05 => Ok(Self::GetBlockBodies),
-            0x06 => Ok(Self::BlockBodies),
-            0x07 => Ok(Self::NewBlock),
-            0x08 => Ok(Self::NewPooledTransactionHashes),
-            0x09 => Ok(Self::GetPooledTransactions),
-            0x0a => Ok(Self::PooledTransactions),
-            0x0d => Ok(Self::GetNodeData),
-            0x0e => Ok(Self::NodeData),
-            0x0f => Ok(Self::GetReceipts),
-            0x10 => Ok(Self::Receipts),
-            _ => Err("Invalid message ID"),
-        }
+        u8::try_from(value).ok().and_then(Self::from_u8).ok_or("Invalid message ID")
     }
 }
 
@@ -494,12 +671,16 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::MessageError;
+    use super::{DecodeErrorKind, MessageError, MessageTooLarge};
     use crate::{
-        message::RequestPair, EthMessage, EthMessageID, GetNodeData, NodeData, ProtocolMessage,
+        message::RequestPair, BlockRangeUpdate, EthMessage, EthMessageID, EthVersion,
+        GetBlockHeaders, GetNodeData, NodeData, ProtocolMessage, Transactions,
     };
     use alloy_rlp::{Decodable, Encodable, Error};
-    use reth_primitives::hex;
+    use reth_primitives::{
+        hex, BlockHashOrNumber, HeadersDirection, Signature, Transaction, TransactionSigned,
+        TxLegacy, B256,
+    };
 
     fn encode<T: Encodable>(value: T) -> Vec<u8> {
         let mut buf = vec![];
@@ -507,6 +688,81 @@ mod tests {
         buf
     }
 
+    #[test]
+    fn test_summary() {
+        let tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy::default()),
+            Signature::default(),
+        );
+        let txs = Transactions(vec![tx; 3]);
+        let bytes = txs.length();
+        assert_eq!(
+            EthMessage::Transactions(txs).summary(),
+            format!("Transactions(count=3, bytes={bytes})")
+        );
+
+        let request = EthMessage::GetBlockHeaders(RequestPair {
+            request_id: 7,
+            message: GetBlockHeaders {
+                start_block: BlockHashOrNumber::Number(100),
+                limit: 64,
+                skip: 0,
+                direction: HeadersDirection::Rising,
+            },
+        });
+        assert_eq!(
+            request.summary(),
+            "GetBlockHeaders(id=7, start=100, limit=64, skip=0, direction=Rising)"
+        );
+    }
+
+    #[test]
+    fn test_encode_checked() {
+        let tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy::default()),
+            Signature::default(),
+        );
+        let msg = EthMessage::Transactions(Transactions(vec![tx; 100]));
+        // the message id is part of the encoding
+        let size = msg.length() + 1;
+
+        // one byte over the limit
+        let mut buf = vec![];
+        assert_eq!(
+            msg.encode_checked(&mut buf, size - 1),
+            Err(MessageTooLarge { size, max_bytes: size - 1 })
+        );
+        assert!(buf.is_empty());
+
+        // exactly at the limit
+        msg.encode_checked(&mut buf, size).unwrap();
+        assert_eq!(buf.len(), size);
+        assert_eq!(buf, encode(ProtocolMessage::from(msg)));
+    }
+
+    #[test]
+    fn test_decode_error_kind() {
+        let decode = |version, buf: &[u8]| {
+            ProtocolMessage::decode_message(version, &mut &buf[..]).unwrap_err().kind()
+        };
+
+        let node_data =
+            EthMessage::NodeData(RequestPair { request_id: 1337, message: NodeData(vec![]) });
+        let buf = encode(ProtocolMessage::from(node_data));
+        assert_eq!(decode(EthVersion::Eth67, &buf), DecodeErrorKind::UnsupportedVersion);
+
+        assert_eq!(decode(EthVersion::Eth66, &[]), DecodeErrorKind::Truncated);
+        assert_eq!(decode(EthVersion::Eth66, &buf[..buf.len() - 1]), DecodeErrorKind::Truncated);
+
+        // unknown message id
+        assert_eq!(decode(EthVersion::Eth66, &[0x7f, 0xc0]), DecodeErrorKind::Malformed);
+        // a string where a list is expected
+        assert_eq!(
+            decode(EthVersion::Eth66, &[EthMessageID::NodeData as u8, 0x80]),
+            DecodeErrorKind::Malformed
+        );
+    }
+
     #[test]
     fn test_removed_message_at_eth67() {
         let get_node_data =
@@ -526,6 +782,44 @@ mod tests {
         assert!(matches!(msg, Err(MessageError::Invalid(..))));
     }
 
+    #[test]
+    fn block_range_update_roundtrip() {
+        let update = BlockRangeUpdate {
+            earliest: 1,
+            latest: 1_000,
+            latest_hash: B256::with_last_byte(0xaa),
+        };
+        let buf = encode(ProtocolMessage::from(EthMessage::BlockRangeUpdate(update)));
+        assert_eq!(buf[0], EthMessageID::BlockRangeUpdate.as_u8());
+
+        let msg = ProtocolMessage::decode_message(EthVersion::Eth69, &mut &buf[..]).unwrap();
+        assert_eq!(msg.message_type, EthMessageID::BlockRangeUpdate);
+        assert_eq!(msg.message, EthMessage::BlockRangeUpdate(update));
+    }
+
+    #[test]
+    fn block_range_update_before_eth69() {
+        let buf = encode(ProtocolMessage::from(EthMessage::BlockRangeUpdate(
+            BlockRangeUpdate::default(),
+        )));
+        for version in [EthVersion::Eth66, EthVersion::Eth67, EthVersion::Eth68] {
+            let msg = ProtocolMessage::decode_message(version, &mut &buf[..]);
+            assert!(matches!(
+                msg,
+                Err(MessageError::Invalid(v, EthMessageID::BlockRangeUpdate)) if v == version
+            ));
+        }
+    }
+
+    #[test]
+    fn max_message_id_per_version() {
+        assert_eq!(
+            EthMessageID::max_for_version(EthVersion::Eth68),
+            EthMessageID::Receipts.as_u8()
+        );
+        assert_eq!(EthMessageID::max_for_version(EthVersion::Eth69), EthMessageID::max());
+    }
+
     #[test]
     fn request_pair_encode() {
         let request_pair = RequestPair { request_id: 1337, message: vec![5u8] };
@@ -568,4 +862,22 @@ mod tests {
         let result = RequestPair::<Vec<u8>>::decode(&mut &*raw_pair);
         assert!(matches!(result, Err(Error::UnexpectedLength)));
     }
+
+    #[test]
+    fn eth_message_id_all() {
+        let all = EthMessageID::all();
+        assert_eq!(all.len(), 16);
+        assert_eq!(all.last().map(EthMessageID::as_u8), Some(EthMessageID::max()));
+
+        for id in all {
+            assert_eq!(EthMessageID::from_u8(id.as_u8()), Some(*id));
+            assert_eq!(EthMessageID::try_from(id.as_u8() as usize), Ok(*id));
+        }
+
+        // every byte not covered by `all` is rejected
+        let unknown = (0..=u8::MAX).filter(|byte| all.iter().all(|id| id.as_u8() != *byte));
+        for byte in unknown {
+            assert_eq!(EthMessageID::from_u8(byte), None);
+        }
+    }
 }
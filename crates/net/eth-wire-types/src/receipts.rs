@@ -1,6 +1,6 @@
 //! Implements the `GetReceipts` and `Receipts` message types.
 
-use alloy_rlp::{RlpDecodableWrapper, RlpEncodableWrapper};
+use alloy_rlp::{Decodable, RlpDecodableWrapper, RlpEncodableWrapper};
 use reth_codecs_derive::derive_arbitrary;
 use reth_primitives::{ReceiptWithBloom, B256};
 
@@ -26,8 +26,100 @@ pub struct Receipts(
     pub Vec<Vec<ReceiptWithBloom>>,
 );
 
+impl Receipts {
+    /// Decodes a [`Receipts`] message, enforcing the given limits while decoding.
+    ///
+    /// The size of the encoded message is checked against `max_bytes` from its RLP header alone,
+    /// and the number of blocks and receipts per block are checked as entries are decoded, so an
+    /// oversized response is rejected before it is fully allocated.
+    pub fn decode_bounded(
+        buf: &mut &[u8],
+        max_blocks: usize,
+        max_receipts_per_block: usize,
+        max_bytes: usize,
+    ) -> Result<Self, ReceiptsDecodeError> {
+        let mut payload = *buf;
+        let header = alloy_rlp::Header::decode(&mut payload)?;
+        let len = buf.len() - payload.len() + header.payload_length;
+        if len > max_bytes {
+            return Err(ReceiptsDecodeError::TooLarge { len, max: max_bytes })
+        }
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString.into())
+        }
+        if payload.len() < header.payload_length {
+            return Err(alloy_rlp::Error::InputTooShort.into())
+        }
+
+        let (mut payload, rest) = payload.split_at(header.payload_length);
+        let mut blocks = Vec::new();
+        while !payload.is_empty() {
+            if blocks.len() == max_blocks {
+                return Err(ReceiptsDecodeError::TooManyBlocks { max: max_blocks })
+            }
+
+            let header = alloy_rlp::Header::decode(&mut payload)?;
+            if !header.list {
+                return Err(alloy_rlp::Error::UnexpectedString.into())
+            }
+            if payload.len() < header.payload_length {
+                return Err(alloy_rlp::Error::InputTooShort.into())
+            }
+
+            let (mut block_payload, block_rest) = payload.split_at(header.payload_length);
+            let mut receipts = Vec::new();
+            while !block_payload.is_empty() {
+                if receipts.len() == max_receipts_per_block {
+                    return Err(ReceiptsDecodeError::TooManyReceipts {
+                        block: blocks.len(),
+                        max: max_receipts_per_block,
+                    })
+                }
+                receipts.push(ReceiptWithBloom::decode(&mut block_payload)?);
+            }
+
+            blocks.push(receipts);
+            payload = block_rest;
+        }
+
+        *buf = rest;
+        Ok(Self(blocks))
+    }
+}
+
+/// Errors when decoding a [`Receipts`] message with [`Receipts::decode_bounded`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ReceiptsDecodeError {
+    /// The message is not valid RLP.
+    #[error(transparent)]
+    Rlp(#[from] alloy_rlp::Error),
+    /// The encoded message exceeds the byte limit.
+    #[error("receipts message is {len} bytes, exceeding the limit of {max}")]
+    TooLarge {
+        /// Encoded length of the message.
+        len: usize,
+        /// The byte limit.
+        max: usize,
+    },
+    /// The message contains receipts for more blocks than allowed.
+    #[error("receipts message contains more than {max} blocks")]
+    TooManyBlocks {
+        /// The block limit.
+        max: usize,
+    },
+    /// A block contains more receipts than allowed.
+    #[error("block at index {block} contains more than {max} receipts")]
+    TooManyReceipts {
+        /// Index of the block in the message.
+        block: usize,
+        /// The per-block receipt limit.
+        max: usize,
+    },
+}
+
 #[cfg(test)]
 mod tests {
+    use super::ReceiptsDecodeError;
     use crate::{message::RequestPair, GetReceipts, Receipts};
     use alloy_rlp::{Decodable, Encodable};
     use reth_primitives::{hex, Log, Receipt, ReceiptWithBloom, TxType};
@@ -151,4 +243,51 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    fn decode_bounded() {
+        let receipt = || ReceiptWithBloom {
+            receipt: Receipt { tx_type: TxType::Eip1559, ..Default::default() },
+            bloom: Default::default(),
+        };
+        let receipts = Receipts(vec![vec![receipt(), receipt()], vec![], vec![receipt()]]);
+        let encoded = alloy_rlp::encode(&receipts);
+
+        assert_eq!(
+            Receipts::decode_bounded(&mut &encoded[..], 3, 2, encoded.len()).unwrap(),
+            receipts
+        );
+        assert_eq!(
+            Receipts::decode_bounded(&mut &encoded[..], 2, 2, encoded.len()),
+            Err(ReceiptsDecodeError::TooManyBlocks { max: 2 })
+        );
+        assert_eq!(
+            Receipts::decode_bounded(&mut &encoded[..], 3, 1, encoded.len()),
+            Err(ReceiptsDecodeError::TooManyReceipts { block: 0, max: 1 })
+        );
+        assert_eq!(
+            Receipts::decode_bounded(&mut &encoded[..], 3, 2, encoded.len() - 1),
+            Err(ReceiptsDecodeError::TooLarge { len: encoded.len(), max: encoded.len() - 1 })
+        );
+    }
+
+    #[test]
+    fn decode_bounded_stops_at_limit() {
+        // a valid block followed by a malformed one, which is never decoded once the block
+        // limit is hit
+        let mut payload = alloy_rlp::encode(vec![ReceiptWithBloom::default()]);
+        payload.extend_from_slice(&hex!("c28100"));
+        let mut data = vec![];
+        alloy_rlp::Header { list: true, payload_length: payload.len() }.encode(&mut data);
+        data.extend_from_slice(&payload);
+
+        assert_eq!(
+            Receipts::decode_bounded(&mut &data[..], 1, 16, data.len()),
+            Err(ReceiptsDecodeError::TooManyBlocks { max: 1 })
+        );
+        assert!(matches!(
+            Receipts::decode_bounded(&mut &data[..], 2, 16, data.len()),
+            Err(ReceiptsDecodeError::Rlp(_))
+        ));
+    }
 }
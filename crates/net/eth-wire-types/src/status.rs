@@ -3,7 +3,7 @@ use alloy_genesis::Genesis;
 use alloy_rlp::{RlpDecodable, RlpEncodable};
 use reth_chainspec::{Chain, ChainSpec, NamedChain, MAINNET};
 use reth_codecs_derive::derive_arbitrary;
-use reth_primitives::{hex, ForkId, Hardfork, Head, B256, U256};
+use reth_primitives::{hex, ForkId, GotExpected, Hardfork, Head, B256, U256};
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 use std::fmt::{Debug, Display};
@@ -82,6 +82,90 @@ impl Status {
             .total_difficulty(head.total_difficulty)
             .forkid(spec.fork_id(head))
     }
+
+    /// Returns `true` if the advertised `version` is an [`EthVersion`] we implement.
+    ///
+    /// Statuses with unknown versions still decode, so the handshake can reject them explicitly.
+    pub fn is_known_version(&self) -> bool {
+        EthVersion::try_from(self.version).is_ok()
+    }
+
+    /// Returns `true` if both statuses are on the same network and fork, i.e. they have the same
+    /// chain id, genesis hash and fork id.
+    ///
+    /// The head-specific fields (best block hash and total difficulty) are ignored.
+    pub fn same_network(&self, other: &Self) -> bool {
+        self.chain == other.chain && self.genesis == other.genesis && self.forkid == other.forkid
+    }
+
+    /// Returns `true` if the fork id changed compared to the `previous` status, e.g. because the
+    /// head crossed a fork boundary, so the new status should be re-announced to peers by
+    /// re-handshaking.
+    pub fn needs_reannounce(&self, previous: &Self) -> bool {
+        self.forkid != previous.forkid
+    }
+
+    /// Returns the fields in which the `other` status, e.g. a peer's, differs from this one.
+    ///
+    /// Each field of the returned [`StatusDiff`] is only set if the values differ, with `other`'s
+    /// value as the `got` value.
+    pub fn diff(&self, other: &Self) -> StatusDiff {
+        fn field<T: PartialEq>(expected: T, got: T) -> Option<GotExpected<T>> {
+            (expected != got).then_some(GotExpected { got, expected })
+        }
+
+        StatusDiff {
+            chain: field(self.chain, other.chain),
+            genesis: field(self.genesis, other.genesis),
+            forkid: field(self.forkid, other.forkid),
+            blockhash: field(self.blockhash, other.blockhash),
+            total_difficulty: field(self.total_difficulty, other.total_difficulty),
+        }
+    }
+
+    /// Returns the status as a JSON value, e.g. for admin tooling or logs.
+    #[cfg(feature = "serde")]
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).expect("status serialization is infallible")
+    }
+
+    /// Reconstructs a status from a JSON value as returned by [`Status::to_json`].
+    #[cfg(feature = "serde")]
+    pub fn from_json(value: serde_json::Value) -> Result<Self, FromJsonError> {
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+/// Error returned by [`Status::from_json`] if the value is not a valid status.
+#[cfg(feature = "serde")]
+#[derive(Debug, thiserror::Error)]
+#[error("invalid status json: {0}")]
+pub struct FromJsonError(#[from] serde_json::Error);
+
+/// The fields in which two [`Status`] messages differ, see [`Status::diff`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatusDiff {
+    /// The chain ids, if they differ.
+    pub chain: Option<GotExpected<Chain>>,
+    /// The genesis hashes, if they differ.
+    pub genesis: Option<GotExpected<B256>>,
+    /// The fork ids, if they differ.
+    pub forkid: Option<GotExpected<ForkId>>,
+    /// The best block hashes, if they differ.
+    pub blockhash: Option<GotExpected<B256>>,
+    /// The total difficulties, if they differ.
+    pub total_difficulty: Option<GotExpected<U256>>,
+}
+
+impl StatusDiff {
+    /// Returns `true` if none of the fields differ.
+    pub const fn is_empty(&self) -> bool {
+        self.chain.is_none() &&
+            self.genesis.is_none() &&
+            self.forkid.is_none() &&
+            self.blockhash.is_none() &&
+            self.total_difficulty.is_none()
+    }
 }
 
 impl Display for Status {
@@ -148,6 +232,21 @@ impl Default for Status {
     }
 }
 
+/// Announces the range of blocks a peer can serve, introduced in eth/69.
+///
+/// Sent whenever the peer's served range changes.
+#[derive_arbitrary(rlp)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct BlockRangeUpdate {
+    /// The earliest block the peer can serve.
+    pub earliest: u64,
+    /// The latest block the peer can serve.
+    pub latest: u64,
+    /// The hash of the latest block.
+    pub latest_hash: B256,
+}
+
 /// Builder for [`Status`] messages.
 ///
 /// # Example
@@ -228,12 +327,12 @@ impl StatusBuilder {
 
 #[cfg(test)]
 mod tests {
-    use crate::{EthVersion, Status};
+    use crate::{EthVersion, Status, StatusDiff};
     use alloy_genesis::Genesis;
     use alloy_rlp::{Decodable, Encodable};
     use rand::Rng;
     use reth_chainspec::{Chain, ChainSpec, ForkCondition, NamedChain};
-    use reth_primitives::{hex, ForkHash, ForkId, Hardfork, Head, B256, U256};
+    use reth_primitives::{hex, ForkHash, ForkId, GotExpected, Hardfork, Head, B256, U256};
     use std::str::FromStr;
 
     #[test]
@@ -404,4 +503,88 @@ mod tests {
         assert_eq!(status.blockhash, head_hash);
         assert_eq!(status.genesis, genesis_hash);
     }
+
+    #[test]
+    fn same_network_ignores_head() {
+        let status = Status::default();
+        let other = Status {
+            blockhash: B256::with_last_byte(1),
+            total_difficulty: U256::from(1337),
+            ..Default::default()
+        };
+        assert_ne!(status, other);
+        assert!(status.same_network(&other));
+
+        let other_fork = Status {
+            forkid: ForkId { hash: ForkHash([0xde, 0xad, 0xbe, 0xef]), next: 0 },
+            ..Default::default()
+        };
+        assert!(!status.same_network(&other_fork));
+
+        let other_chain = Status { chain: Chain::from_id(1337), ..Default::default() };
+        assert!(!status.same_network(&other_chain));
+    }
+
+    #[test]
+    fn needs_reannounce_on_fork_id_change() {
+        let previous = Status::default();
+        assert!(!previous.needs_reannounce(&previous));
+
+        let status = Status {
+            forkid: ForkId { hash: ForkHash([0xde, 0xad, 0xbe, 0xef]), next: 0 },
+            ..previous
+        };
+        assert!(status.needs_reannounce(&previous));
+    }
+
+    #[test]
+    fn status_diff() {
+        let status = Status::default();
+        assert!(status.diff(&status).is_empty());
+
+        let other = Status {
+            blockhash: B256::with_last_byte(1),
+            total_difficulty: U256::from(1),
+            ..status
+        };
+        assert_eq!(
+            status.diff(&other),
+            StatusDiff {
+                blockhash: Some(GotExpected {
+                    got: B256::with_last_byte(1),
+                    expected: status.blockhash
+                }),
+                total_difficulty: Some(GotExpected {
+                    got: U256::from(1),
+                    expected: status.total_difficulty
+                }),
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn decode_status_unknown_version() {
+        // same as `decode_eth_status_message`, but advertising version 99
+        let data = hex!("f85663018a07aac59dabcdd74bc567a0feb27336ca7923f8fab3bd617fcb6e75841538f71c1bcfc267d7838489d9e13da0d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3c684b715077d80");
+        let status = Status::decode(&mut &data[..]).unwrap();
+        assert_eq!(status.version, 99);
+        assert_eq!(status.chain, Chain::from_named(NamedChain::Mainnet));
+        assert!(!status.is_known_version());
+
+        let known = Status { version: EthVersion::Eth68 as u8, ..status };
+        assert!(known.is_known_version());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn status_json_roundtrip() {
+        let status = Status {
+            total_difficulty: U256::from(58_750_003_716_598_352_816_469u128),
+            ..Default::default()
+        };
+        assert_eq!(Status::from_json(status.to_json()).unwrap(), status);
+
+        assert!(Status::from_json(serde_json::json!({ "version": 68 })).is_err());
+    }
 }
@@ -21,6 +21,13 @@ pub enum EthVersion {
 
     /// The `eth` protocol version 68.
     Eth68 = 68,
+
+    /// The `eth` protocol version 69.
+    ///
+    /// Of the eth/69 changes only the `BlockRangeUpdate` message is supported, the eth/69 `Status`
+    /// encoding is not. So this version is neither advertised nor negotiable: it is not parsed
+    /// from capabilities or status messages.
+    Eth69 = 69,
 }
 
 impl EthVersion {
@@ -35,6 +42,8 @@ impl EthVersion {
                 // eth/67,68 are eth/66 minus GetNodeData and NodeData messages
                 13
             }
+            // eth/69 adds the BlockRangeUpdate message
+            Self::Eth69 => 14,
         }
     }
 
@@ -52,6 +61,29 @@ impl EthVersion {
     pub const fn is_eth68(&self) -> bool {
         matches!(self, Self::Eth68)
     }
+
+    /// Returns true if the version is eth/69
+    pub const fn is_eth69(&self) -> bool {
+        matches!(self, Self::Eth69)
+    }
+
+    /// Returns true if `NewPooledTransactionHashes` announcements include transaction types and
+    /// sizes, which was introduced in eth/68.
+    pub const fn supports_tx_type_in_announcements(&self) -> bool {
+        *self as u8 >= Self::Eth68 as u8
+    }
+
+    /// Returns true if the version supports the `GetNodeData` and `NodeData` messages, which were
+    /// removed in eth/67.
+    pub const fn supports_node_data(&self) -> bool {
+        (*self as u8) < Self::Eth67 as u8
+    }
+
+    /// Returns true if the version supports the `BlockRangeUpdate` message, which was introduced
+    /// in eth/69.
+    pub const fn supports_block_range_update(&self) -> bool {
+        *self as u8 >= Self::Eth69 as u8
+    }
 }
 
 /// Allow for converting from a `&str` to an `EthVersion`.
@@ -123,6 +155,7 @@ impl From<EthVersion> for &'static str {
             EthVersion::Eth66 => "66",
             EthVersion::Eth67 => "67",
             EthVersion::Eth68 => "68",
+            EthVersion::Eth69 => "69",
         }
     }
 }
@@ -146,4 +179,29 @@ mod tests {
         assert_eq!(EthVersion::Eth68, "68".parse().unwrap());
         assert_eq!(Err(ParseVersionError("69".to_string())), "69".parse::<EthVersion>());
     }
+
+    #[test]
+    fn test_eth_version_ordering() {
+        assert!(EthVersion::Eth66 < EthVersion::Eth67);
+        assert!(EthVersion::Eth67 < EthVersion::Eth68);
+        assert_eq!(EthVersion::LATEST, EthVersion::Eth68);
+        assert_eq!(
+            [EthVersion::Eth68, EthVersion::Eth66, EthVersion::Eth67].iter().max(),
+            Some(&EthVersion::Eth68)
+        );
+    }
+
+    #[test]
+    fn test_eth_version_features() {
+        assert!(EthVersion::Eth68.supports_tx_type_in_announcements());
+        assert!(!EthVersion::Eth67.supports_tx_type_in_announcements());
+        assert!(!EthVersion::Eth66.supports_tx_type_in_announcements());
+
+        assert!(EthVersion::Eth66.supports_node_data());
+        assert!(!EthVersion::Eth67.supports_node_data());
+        assert!(!EthVersion::Eth68.supports_node_data());
+
+        assert!(EthVersion::Eth69.supports_block_range_update());
+        assert!(!EthVersion::Eth68.supports_block_range_update());
+    }
 }
@@ -328,7 +328,7 @@ impl SharedCapability {
     /// Returns the number of protocol messages supported by this capability.
     pub const fn num_messages(&self) -> u8 {
         match self {
-            Self::Eth { version: _version, .. } => EthMessageID::max() + 1,
+            Self::Eth { version, .. } => EthMessageID::max_for_version(*version) + 1,
             Self::UnknownCapability { messages, .. } => *messages,
         }
     }
@@ -565,6 +565,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn eth_69_not_negotiable() {
+        // the eth/69 status encoding is not supported yet
+        assert!(matches!(
+            SharedCapability::new("eth", 69, MAX_RESERVED_MESSAGE_ID + 1, 14),
+            Err(SharedCapabilityError::UnsupportedVersion(_))
+        ));
+    }
+
     #[test]
     fn from_eth_67() {
         let capability = SharedCapability::new("eth", 67, MAX_RESERVED_MESSAGE_ID + 1, 13).unwrap();
@@ -100,3 +100,18 @@ pub enum EthHandshakeError {
         maximum: usize,
     },
 }
+
+/// Error returned by [`decode_eth_handshake`](crate::decode_eth_handshake), identifying the
+/// handshake step that failed.
+#[derive(thiserror::Error, Debug)]
+pub enum EthHandshakeDecodeError {
+    /// No `eth` version could be negotiated from the hello messages.
+    #[error("hello: {0}")]
+    Hello(#[from] P2PStreamError),
+    /// The peer's status message could not be decoded.
+    #[error("invalid status message: {0}")]
+    InvalidStatusMessage(#[from] MessageError),
+    /// The peer's message is not a status message or is incompatible with the local status.
+    #[error("invalid status: {0}")]
+    InvalidStatus(#[from] EthHandshakeError),
+}
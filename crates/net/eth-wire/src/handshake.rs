@@ -0,0 +1,209 @@
+//! Validation of the messages exchanged in the `eth` handshake.
+
+use crate::{
+    capability::SharedCapabilities,
+    errors::{EthHandshakeDecodeError, EthHandshakeError},
+    EthMessage, EthVersion, HelloMessage, HelloMessageWithProtocols, ProtocolMessage, Status,
+};
+use reth_primitives::{ForkFilter, GotExpected};
+
+/// Validates the handshake messages received from a peer, in order: its [`HelloMessage`] and its
+/// encoded `eth` [`Status`] message.
+///
+/// The `eth` version is negotiated from `local_hello` and the peer's hello the same way the `p2p`
+/// handshake does, picking the highest version both sides support. The status message is then
+/// decoded with that version and validated against `local_status` and `fork_filter`, the same way
+/// [`UnauthedEthStream::handshake`](crate::UnauthedEthStream::handshake) does. The version of
+/// `local_status` is replaced by the negotiated one.
+///
+/// Returns the negotiated [`EthVersion`] and the peer's [`Status`].
+pub fn decode_eth_handshake(
+    local_hello: &HelloMessageWithProtocols,
+    local_status: &Status,
+    fork_filter: &ForkFilter,
+    hello: &HelloMessage,
+    status_msg: &[u8],
+) -> Result<(EthVersion, Status), EthHandshakeDecodeError> {
+    let version =
+        SharedCapabilities::try_new(local_hello.protocols.clone(), hello.capabilities.clone())?
+            .eth_version()?;
+
+    let msg = ProtocolMessage::decode_message(version, &mut &status_msg[..])?;
+    let EthMessage::Status(status) = msg.message else {
+        return Err(EthHandshakeError::NonStatusMessageInHandshake.into())
+    };
+
+    let mut local_status = *local_status;
+    local_status.set_eth_version(version);
+    validate_status(&local_status, &status, fork_filter)?;
+
+    Ok((version, status))
+}
+
+/// Validates the peer's [`Status`] against our own.
+///
+/// The following checks should match the checks in go-ethereum:
+/// <https://github.com/ethereum/go-ethereum/blob/9244d5cd61f3ea5a7645fdf2a1a96d53421e412f/eth/protocols/eth/handshake.go#L87-L89>
+pub(crate) fn validate_status(
+    status: &Status,
+    resp: &Status,
+    fork_filter: &ForkFilter,
+) -> Result<(), EthHandshakeError> {
+    if status.genesis != resp.genesis {
+        return Err(EthHandshakeError::MismatchedGenesis(
+            GotExpected { expected: status.genesis, got: resp.genesis }.into(),
+        ))
+    }
+
+    if status.version != resp.version {
+        return Err(EthHandshakeError::MismatchedProtocolVersion(GotExpected {
+            got: resp.version,
+            expected: status.version,
+        }))
+    }
+
+    if status.chain != resp.chain {
+        return Err(EthHandshakeError::MismatchedChain(GotExpected {
+            got: resp.chain,
+            expected: status.chain,
+        }))
+    }
+
+    // TD at mainnet block #7753254 is 76 bits. If it becomes 100 million times
+    // larger, it will still fit within 100 bits
+    if status.total_difficulty.bit_len() > 100 {
+        return Err(EthHandshakeError::TotalDifficultyBitLenTooLarge {
+            got: status.total_difficulty.bit_len(),
+            maximum: 100,
+        })
+    }
+
+    fork_filter.validate(resp.forkid).map_err(EthHandshakeError::InvalidFork)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        errors::{P2PHandshakeError, P2PStreamError},
+        message::MessageError,
+        test_utils::{eth_handshake, eth_hello},
+        Capability,
+    };
+    use reth_primitives::B256;
+
+    fn encode(message: EthMessage) -> Vec<u8> {
+        alloy_rlp::encode(ProtocolMessage::from(message))
+    }
+
+    #[test]
+    fn decode_handshake() {
+        let (status, fork_filter) = eth_handshake();
+        let (local_hello, _) = eth_hello();
+        let hello = local_hello.message();
+
+        let (version, their_status) = decode_eth_handshake(
+            &local_hello,
+            &status,
+            &fork_filter,
+            &hello,
+            &encode(EthMessage::Status(status)),
+        )
+        .unwrap();
+        assert_eq!(version, EthVersion::Eth67);
+        assert_eq!(their_status, status);
+    }
+
+    #[test]
+    fn decode_handshake_negotiates_highest_shared_version() {
+        let (mut status, fork_filter) = eth_handshake();
+        let (mut local_hello, _) = eth_hello();
+        local_hello.protocols =
+            vec![EthVersion::Eth68.into(), EthVersion::Eth67.into(), EthVersion::Eth66.into()];
+        status.set_eth_version(EthVersion::Eth68);
+
+        let mut hello = local_hello.message();
+        hello.capabilities = vec![Capability::eth_66(), Capability::eth_67()];
+        let their_status = Status { version: EthVersion::Eth67 as u8, ..status };
+
+        let (version, decoded) = decode_eth_handshake(
+            &local_hello,
+            &status,
+            &fork_filter,
+            &hello,
+            &encode(EthMessage::Status(their_status)),
+        )
+        .unwrap();
+        assert_eq!(version, EthVersion::Eth67);
+        assert_eq!(decoded, their_status);
+    }
+
+    #[test]
+    fn decode_handshake_no_shared_version() {
+        let (status, fork_filter) = eth_handshake();
+        let (local_hello, _) = eth_hello();
+        let mut hello = local_hello.message();
+        hello.capabilities = vec![Capability::eth_68()];
+
+        let res = decode_eth_handshake(
+            &local_hello,
+            &status,
+            &fork_filter,
+            &hello,
+            &encode(EthMessage::Status(status)),
+        );
+        assert!(matches!(
+            res,
+            Err(EthHandshakeDecodeError::Hello(P2PStreamError::HandshakeError(
+                P2PHandshakeError::NoSharedCapabilities
+            )))
+        ));
+    }
+
+    #[test]
+    fn decode_handshake_invalid_status_message() {
+        let (status, fork_filter) = eth_handshake();
+        let (local_hello, _) = eth_hello();
+        let hello = local_hello.message();
+
+        let res = decode_eth_handshake(&local_hello, &status, &fork_filter, &hello, &[0x00, 0xc0]);
+        assert!(matches!(
+            res,
+            Err(EthHandshakeDecodeError::InvalidStatusMessage(MessageError::RlpError(_)))
+        ));
+
+        let res = decode_eth_handshake(
+            &local_hello,
+            &status,
+            &fork_filter,
+            &hello,
+            &encode(EthMessage::NewBlockHashes(Default::default())),
+        );
+        assert!(matches!(
+            res,
+            Err(EthHandshakeDecodeError::InvalidStatus(
+                EthHandshakeError::NonStatusMessageInHandshake
+            ))
+        ));
+    }
+
+    #[test]
+    fn decode_handshake_mismatched_status() {
+        let (status, fork_filter) = eth_handshake();
+        let (local_hello, _) = eth_hello();
+        let hello = local_hello.message();
+
+        let their_status = Status { genesis: B256::ZERO, ..status };
+        let res = decode_eth_handshake(
+            &local_hello,
+            &status,
+            &fork_filter,
+            &hello,
+            &encode(EthMessage::Status(their_status)),
+        );
+        assert!(matches!(
+            res,
+            Err(EthHandshakeDecodeError::InvalidStatus(EthHandshakeError::MismatchedGenesis(_)))
+        ));
+    }
+}
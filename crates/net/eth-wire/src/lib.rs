@@ -17,6 +17,7 @@ pub mod capability;
 mod disconnect;
 pub mod errors;
 mod ethstream;
+mod handshake;
 mod hello;
 pub mod multiplex;
 mod p2pstream;
@@ -35,6 +36,7 @@ pub use crate::{
     capability::Capability,
     disconnect::{CanDisconnect, DisconnectReason},
     ethstream::{EthStream, UnauthedEthStream, MAX_MESSAGE_SIZE},
+    handshake::decode_eth_handshake,
     hello::{HelloMessage, HelloMessageBuilder, HelloMessageWithProtocols},
     p2pstream::{
         DisconnectP2P, P2PMessage, P2PMessageID, P2PStream, ProtocolVersion, UnauthedP2PStream,
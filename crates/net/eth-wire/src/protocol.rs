@@ -54,7 +54,8 @@ impl Protocol {
     /// The number of values needed to represent all message IDs of capability.
     pub fn messages(&self) -> u8 {
         if self.cap.is_eth() {
-            return EthMessageID::max() + 1
+            // all negotiable eth versions share the message id space of the latest one
+            return EthMessageID::max_for_version(EthVersion::LATEST) + 1
         }
         self.messages
     }
@@ -231,6 +231,8 @@ impl ActiveSession {
             EthMessage::Receipts(resp) => {
                 on_response!(resp, GetReceipts)
             }
+            // served block ranges are not tracked yet
+            EthMessage::BlockRangeUpdate(_) => OnIncomingMessageOutcome::Ok,
         }
     }
 
@@ -19,6 +19,15 @@ pub struct DatabaseArgs {
     /// NFS volume.
     #[arg(long = "db.exclusive")]
     pub exclusive: Option<bool>,
+    /// Prefault all database pages into memory at startup, by sequentially reading the whole
+    /// database file.
+    #[arg(long = "db.warm")]
+    pub warm: bool,
+    /// Enable OS read-ahead on the database file. Speeds up sequential scans, e.g. full
+    /// re-execution, but wastes I/O and page cache on random access workloads like serving RPC.
+    /// Disabled by default.
+    #[arg(long = "db.readahead")]
+    pub readahead: Option<bool>,
 }
 
 impl DatabaseArgs {
@@ -27,6 +36,8 @@ impl DatabaseArgs {
         reth_db::mdbx::DatabaseArguments::new(default_client_version())
             .with_log_level(self.log_level)
             .with_exclusive(self.exclusive)
+            .with_warm(self.warm)
+            .with_readahead(self.readahead)
     }
 }
 
@@ -137,4 +148,30 @@ mod tests {
         let cmd = CommandParser::<DatabaseArgs>::try_parse_from(["reth"]).unwrap();
         assert_eq!(cmd.args.log_level, None);
     }
+
+    #[test]
+    fn test_command_parser_with_warm() {
+        let cmd = CommandParser::<DatabaseArgs>::try_parse_from(["reth"]).unwrap();
+        assert!(!cmd.args.warm);
+        assert!(!cmd.args.database_args().warm());
+
+        let cmd = CommandParser::<DatabaseArgs>::try_parse_from(["reth", "--db.warm"]).unwrap();
+        assert!(cmd.args.warm);
+        assert!(cmd.args.database_args().warm());
+    }
+
+    #[test]
+    fn test_command_parser_with_readahead() {
+        let cmd = CommandParser::<DatabaseArgs>::try_parse_from(["reth"]).unwrap();
+        assert_eq!(cmd.args.readahead, None);
+        assert_eq!(cmd.args.database_args().readahead(), None);
+
+        let cmd = CommandParser::<DatabaseArgs>::try_parse_from(["reth", "--db.readahead", "true"])
+            .unwrap();
+        assert_eq!(cmd.args.readahead, Some(true));
+        assert_eq!(cmd.args.database_args().readahead(), Some(true));
+
+        assert!(CommandParser::<DatabaseArgs>::try_parse_from(["reth", "--db.readahead", "maybe"])
+            .is_err());
+    }
 }
@@ -0,0 +1,34 @@
+#![allow(missing_docs)]
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use reth_chainspec::{ChainSpecBuilder, BASE_MAINNET};
+use reth_consensus::Consensus;
+use reth_optimism_consensus::OptimismBeaconConsensus;
+use reth_primitives::{Header, SealedHeader};
+use std::sync::Arc;
+
+/// Benchmarks the standalone header checks of a header range, serially and in parallel.
+pub fn validate_headers(c: &mut Criterion) {
+    let consensus = OptimismBeaconConsensus::new(Arc::new(
+        ChainSpecBuilder::from(&*BASE_MAINNET).bedrock_activated().build(),
+    ));
+
+    let mut group = c.benchmark_group("Validate headers");
+    for size in [1_000u64, 10_000] {
+        let headers = (0..size)
+            .map(|number| {
+                Header { number, base_fee_per_gas: Some(1), ..Default::default() }.seal_slow()
+            })
+            .collect::<Vec<SealedHeader>>();
+
+        group.bench_with_input(BenchmarkId::new("serial", size), &headers, |b, headers| {
+            b.iter(|| headers.iter().try_for_each(|header| consensus.validate_header(header)))
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", size), &headers, |b, headers| {
+            b.iter(|| consensus.validate_headers_parallel(headers))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, validate_headers);
+criterion_main!(benches);
@@ -0,0 +1,33 @@
+//! Per-fork pre-execution validation checks.
+
+/// A single pre-execution check performed by
+/// [`OptimismBeaconConsensus`](crate::OptimismBeaconConsensus).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckKind {
+    /// The block's body must match the commitments in its header.
+    BodyAgainstHeader,
+    /// The withdrawals and the withdrawals root must be absent before Shanghai. From Shanghai, the
+    /// withdrawals root is matched against the body's withdrawals by [`Self::BodyAgainstHeader`].
+    WithdrawalsRoot,
+    /// The withdrawals must be empty, so the withdrawals root is the empty root.
+    ///
+    /// Relies on [`Self::BodyAgainstHeader`] to match the withdrawals root against the body.
+    EmptyWithdrawals,
+    /// The deposit transactions must have unique source hashes.
+    UniqueDepositSourceHashes,
+}
+
+/// Checks performed from Bedrock until Canyon.
+pub(crate) const BEDROCK_CHECKS: &[CheckKind] = &[
+    CheckKind::BodyAgainstHeader,
+    CheckKind::WithdrawalsRoot,
+    CheckKind::UniqueDepositSourceHashes,
+];
+
+/// Checks performed from Canyon, which requires empty withdrawals.
+pub(crate) const CANYON_CHECKS: &[CheckKind] = &[
+    CheckKind::BodyAgainstHeader,
+    CheckKind::WithdrawalsRoot,
+    CheckKind::EmptyWithdrawals,
+    CheckKind::UniqueDepositSourceHashes,
+];
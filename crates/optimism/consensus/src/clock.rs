@@ -0,0 +1,20 @@
+//! Clock abstraction used for timestamp-based header validation.
+
+use std::{fmt::Debug, time::SystemTime};
+
+/// A source of the current unix timestamp.
+pub trait Clock: Debug + Send + Sync {
+    /// Returns the current unix timestamp in seconds.
+    fn now(&self) -> u64;
+}
+
+/// A [`Clock`] backed by the system time.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs()
+    }
+}
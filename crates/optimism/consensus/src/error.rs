@@ -0,0 +1,261 @@
+//! Optimism consensus errors.
+
+use reth_consensus::ConsensusError;
+use reth_primitives::{Address, BlockHash, BlockNumber, GotExpected, Hardfork, TxType, B256};
+
+/// Optimism consensus errors.
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum OpConsensusError {
+    /// Thrown when a block's body does not match its header.
+    #[error("block #{number} ({hash}) body does not match header: {inner}")]
+    BodyHeaderMismatch {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The hash of the block.
+        hash: BlockHash,
+        /// The underlying consensus error.
+        inner: Box<ConsensusError>,
+    },
+    /// Thrown when two deposit transactions in the same block share a source hash.
+    #[error("deposit transaction at index {index} reuses source hash {source_hash}")]
+    DuplicateDepositSourceHash {
+        /// The index of the offending transaction in the block body.
+        index: usize,
+        /// The duplicated source hash.
+        source_hash: B256,
+    },
+    /// Thrown when a block does not start with the L1 attributes deposit transaction.
+    #[error("block does not start with a deposit transaction")]
+    MissingL1AttributesDeposit,
+    /// Thrown when a deposit transaction follows a non-deposit transaction.
+    #[error("deposit transaction at index {index} follows a non-deposit transaction")]
+    DepositAfterNonDeposit {
+        /// The index of the offending deposit transaction in the block body.
+        index: usize,
+    },
+    /// Thrown when a receipt's cumulative gas used is lower than the previous receipt's.
+    #[error("receipt {index} has cumulative gas used {got}, lower than the previous {previous}")]
+    DecreasingCumulativeGas {
+        /// The index of the offending receipt.
+        index: usize,
+        /// The cumulative gas used of the receipt.
+        got: u64,
+        /// The cumulative gas used of the previous receipt.
+        previous: u64,
+    },
+    /// Thrown when a header's gas limit exceeds the configured cap.
+    #[error("gas limit {gas_limit} exceeds cap {cap}")]
+    GasLimitExceedsCap {
+        /// The header's gas limit.
+        gas_limit: u64,
+        /// The configured gas limit cap.
+        cap: u64,
+    },
+    /// Thrown when a transaction's gas limit exceeds the configured per-transaction cap.
+    #[error("transaction at index {index} has gas limit {gas_limit}, exceeding cap {cap}")]
+    TransactionGasLimitExceedsCap {
+        /// The index of the offending transaction in the block body.
+        index: usize,
+        /// The transaction's gas limit.
+        gas_limit: u64,
+        /// The configured per-transaction gas limit cap.
+        cap: u64,
+    },
+    /// Thrown when a header activates a fork by timestamp while Bedrock, which the fork builds
+    /// on, is not active at its number.
+    #[error("block #{number} at timestamp {timestamp} activates {fork} before Bedrock")]
+    ForkBeforeBedrock {
+        /// The timestamp based fork active at the header's timestamp.
+        fork: Hardfork,
+        /// The number of the block.
+        number: BlockNumber,
+        /// The timestamp of the block.
+        timestamp: u64,
+    },
+    /// Thrown when the header has no blob gas used field.
+    #[error("missing blob gas used")]
+    BlobGasUsedMissing,
+    /// Thrown when an RLP encoded block can't be decoded.
+    #[error("failed to decode block: {0}")]
+    BlockDecode(alloy_rlp::Error),
+    /// Thrown when the header's blob gas used is not a multiple of the blob gas per blob.
+    #[error("blob gas used {0} is not a multiple of the blob gas per blob")]
+    BlobGasUsedNotMultiple(u64),
+    /// Thrown when the number of blob versioned hashes of the body does not match the number of
+    /// blobs accounted for by the header's blob gas used.
+    #[error("blob count mismatch: {0}")]
+    BlobCountDiff(GotExpected<u64>),
+    /// Thrown when a header that must have empty extra data does not.
+    #[error("extra data must be empty, got {len} bytes")]
+    NonEmptyExtraData {
+        /// The length of the header's extra data.
+        len: usize,
+    },
+    /// Thrown when the calldata of the L1 attributes deposit can't be decoded.
+    #[error("invalid L1 attributes deposit calldata")]
+    InvalidL1Attributes,
+    /// Thrown when a block's timestamp is beyond the sequencing window of its L1 origin.
+    #[error("block timestamp {timestamp} is more than {window}s after L1 origin {l1_timestamp}")]
+    SequencingWindowExceeded {
+        /// The timestamp of the block.
+        timestamp: u64,
+        /// The timestamp of the block's L1 origin.
+        l1_timestamp: u64,
+        /// The configured sequencing window in seconds.
+        window: u64,
+    },
+    /// Thrown when a block carries EIP-7685 requests before they are activated.
+    #[error("block carries {count} requests before Prague")]
+    UnexpectedRequests {
+        /// The number of requests in the block body.
+        count: usize,
+    },
+    /// Thrown when the number of receipts does not match the number of transactions.
+    #[error("receipt count mismatch: {0}")]
+    ReceiptCountDiff(GotExpected<usize>),
+    /// Thrown when a receipt's type does not match the type of its transaction.
+    #[error("receipt {index} has type {got:?}, expected {expected:?}")]
+    ReceiptTypeMismatch {
+        /// The index of the offending receipt.
+        index: usize,
+        /// The type of the receipt.
+        got: TxType,
+        /// The type of the transaction.
+        expected: TxType,
+    },
+    /// Thrown when the hash of a header at a checkpoint does not match the pinned hash.
+    #[error("block #{number} does not match checkpoint: {hash}")]
+    CheckpointMismatch {
+        /// The number of the block.
+        number: BlockNumber,
+        /// The hash of the header and the pinned hash.
+        hash: GotExpected<B256>,
+    },
+    /// Thrown when the body of a Canyon-active block carries withdrawals.
+    #[error("block body carries {count} withdrawals after Canyon")]
+    UnexpectedBodyWithdrawals {
+        /// The number of withdrawals in the block body.
+        count: usize,
+    },
+    /// Thrown when the RLP encoded block exceeds the configured maximum size.
+    #[error("block is {size} bytes, exceeding the maximum of {max} bytes")]
+    BlockTooLarge {
+        /// The length of the RLP encoded block.
+        size: usize,
+        /// The configured maximum size.
+        max: usize,
+    },
+    /// Thrown when a deposit transaction is malformed.
+    #[error("invalid deposit transaction: {0}")]
+    InvalidDepositTx(#[from] DepositTxError),
+}
+
+/// Deposit transaction validation errors, see
+/// [`validate_deposit_tx`](crate::validation::validate_deposit_tx).
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepositTxError {
+    /// The transaction is not a deposit transaction.
+    #[error("not a deposit transaction")]
+    NotDeposit,
+    /// The deposit has no source hash.
+    #[error("missing source hash")]
+    MissingSourceHash,
+    /// The system deposit is not sent by the system address.
+    #[error("system deposit sent by {got}, expected {expected}")]
+    UnexpectedSender {
+        /// The sender of the deposit.
+        got: Address,
+        /// The system address.
+        expected: Address,
+    },
+    /// The deposit carries a signature.
+    #[error("deposit transactions must not be signed")]
+    UnexpectedSignature,
+    /// The deposit is a system transaction, which were removed in Regolith.
+    #[error("system transactions are not allowed after Regolith")]
+    SystemTransaction,
+    /// The deposit mints ETH but is not a user deposit.
+    #[error("non-user deposit mints {mint} wei")]
+    UnexpectedMint {
+        /// The amount minted by the deposit.
+        mint: u128,
+    },
+}
+
+#[cfg(feature = "serde")]
+impl OpConsensusError {
+    /// Returns the error as a [`ValidationProblem`], e.g. to be returned as JSON by a validation
+    /// service.
+    pub fn as_problem(&self) -> ValidationProblem {
+        // the type strings and codes are stable, codes of removed variants must not be reused
+        let (ty, code) = match self {
+            Self::BodyHeaderMismatch { .. } => ("body_header_mismatch", 1),
+            Self::DuplicateDepositSourceHash { .. } => ("duplicate_deposit_source_hash", 2),
+            Self::MissingL1AttributesDeposit => ("missing_l1_attributes_deposit", 3),
+            Self::DepositAfterNonDeposit { .. } => ("deposit_after_non_deposit", 4),
+            Self::DecreasingCumulativeGas { .. } => ("decreasing_cumulative_gas", 5),
+            Self::GasLimitExceedsCap { .. } => ("gas_limit_exceeds_cap", 6),
+            Self::TransactionGasLimitExceedsCap { .. } => ("transaction_gas_limit_exceeds_cap", 7),
+            Self::ForkBeforeBedrock { .. } => ("fork_before_bedrock", 8),
+            Self::BlobGasUsedMissing => ("blob_gas_used_missing", 9),
+            Self::BlobGasUsedNotMultiple(_) => ("blob_gas_used_not_multiple", 11),
+            Self::BlobCountDiff(_) => ("blob_count_diff", 12),
+            Self::BlockDecode(_) => ("block_decode", 13),
+            Self::InvalidDepositTx(_) => ("invalid_deposit_tx", 14),
+            Self::NonEmptyExtraData { .. } => ("non_empty_extra_data", 15),
+            Self::InvalidL1Attributes => ("invalid_l1_attributes", 16),
+            Self::SequencingWindowExceeded { .. } => ("sequencing_window_exceeded", 17),
+            Self::UnexpectedRequests { .. } => ("unexpected_requests", 18),
+            Self::ReceiptCountDiff(_) => ("receipt_count_diff", 19),
+            Self::ReceiptTypeMismatch { .. } => ("receipt_type_mismatch", 20),
+            Self::CheckpointMismatch { .. } => ("checkpoint_mismatch", 21),
+            Self::UnexpectedBodyWithdrawals { .. } => ("unexpected_body_withdrawals", 22),
+            Self::BlockTooLarge { .. } => ("block_too_large", 23),
+        };
+        ValidationProblem { ty, code, detail: self.to_string() }
+    }
+}
+
+/// A serializable description of an [`OpConsensusError`], see [`OpConsensusError::as_problem`].
+#[cfg(feature = "serde")]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct ValidationProblem {
+    /// Stable identifier of the kind of error.
+    #[serde(rename = "type")]
+    pub ty: &'static str,
+    /// Stable numeric code of the kind of error.
+    pub code: u16,
+    /// Human readable description of the error.
+    pub detail: String,
+}
+
+impl From<OpConsensusError> for ConsensusError {
+    fn from(err: OpConsensusError) -> Self {
+        Self::custom(err)
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn problem_json() {
+        let err = OpConsensusError::GasLimitExceedsCap { gas_limit: 2, cap: 1 };
+        assert_eq!(
+            serde_json::to_value(err.as_problem()).unwrap(),
+            json!({ "type": "gas_limit_exceeds_cap", "code": 6, "detail": "gas limit 2 exceeds cap 1" })
+        );
+
+        let err = OpConsensusError::from(DepositTxError::MissingSourceHash);
+        assert_eq!(
+            serde_json::to_value(err.as_problem()).unwrap(),
+            json!({
+                "type": "invalid_deposit_tx",
+                "code": 14,
+                "detail": "invalid deposit transaction: missing source hash"
+            })
+        );
+    }
+}
@@ -9,29 +9,77 @@
 // The `optimism` feature must be enabled to use this crate.
 #![cfg(feature = "optimism")]
 
+use alloy_rlp::{Decodable, Encodable};
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
 use reth_chainspec::ChainSpec;
 use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
 use reth_consensus_common::validation::{
     validate_against_parent_4844, validate_against_parent_eip1559_base_fee,
     validate_against_parent_hash_number, validate_against_parent_timestamp,
-    validate_block_pre_execution, validate_header_base_fee, validate_header_extradata,
-    validate_header_gas,
+    validate_block_pre_execution, validate_cancun_gas, validate_header_extradata,
+    validate_header_gas, validate_prague_requests, validate_shanghai_withdrawals,
 };
 use reth_primitives::{
-    BlockWithSenders, Header, SealedBlock, SealedHeader, EMPTY_OMMER_ROOT_HASH, U256,
+    proofs::{calculate_ommers_root, calculate_withdrawals_root},
+    Block, BlockNumber, BlockWithSenders, GotExpected, Hardfork, Header, Receipt, SealedBlock,
+    SealedHeader, B256, EMPTY_OMMER_ROOT_HASH, U256,
 };
-use std::{sync::Arc, time::SystemTime};
+#[cfg(feature = "metrics")]
+use std::time::Instant;
+use std::{collections::HashMap, sync::Arc, time::SystemTime};
+use validation::{
+    ensure_base_fee_present, ensure_empty_extra_data, ensure_empty_ommers_root,
+    ensure_tx_gas_limits_within, ensure_unique_deposit_source_hashes,
+    ensure_within_sequencing_window, ensure_zero_difficulty, ensure_zero_nonce,
+    expected_withdrawals_root_kind, validate_no_body_withdrawals, WithdrawalsRootKind,
+};
+
+mod checks;
+pub use checks::CheckKind;
+
+mod clock;
+pub use clock::{Clock, SystemClock};
+
+#[cfg(feature = "metrics")]
+mod metrics;
+
+mod error;
+#[cfg(feature = "serde")]
+pub use error::ValidationProblem;
+pub use error::{DepositTxError, OpConsensusError};
 
-mod validation;
+pub mod proof;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
+
+pub mod validation;
 pub use validation::validate_block_post_execution;
 
 /// Optimism consensus implementation.
 ///
 /// Provides basic checks as outlined in the execution specs.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone)]
 pub struct OptimismBeaconConsensus {
     /// Configuration
     chain_spec: Arc<ChainSpec>,
+    /// Optional clock and the maximum number of seconds a post-Bedrock header's timestamp may be
+    /// ahead of it.
+    clock: Option<(Arc<dyn Clock>, u64)>,
+    /// Optional maximum gas limit of a header.
+    gas_limit_cap: Option<u64>,
+    /// Optional maximum gas limit of a transaction.
+    per_tx_gas_cap: Option<u64>,
+    /// Optional maximum number of seconds a block's timestamp may be ahead of its L1 origin.
+    sequencing_window: Option<u64>,
+    /// Hashes of known-good blocks by number, headers at these numbers must match.
+    checkpoints: HashMap<BlockNumber, B256>,
+    /// Optional maximum length of an RLP encoded block.
+    max_block_rlp_size: Option<usize>,
+    /// Validation duration metrics.
+    #[cfg(feature = "metrics")]
+    metrics: metrics::ConsensusMetrics,
 }
 
 impl OptimismBeaconConsensus {
@@ -42,14 +90,393 @@ impl OptimismBeaconConsensus {
     /// If given chain spec is not optimism [`ChainSpec::is_optimism`]
     pub fn new(chain_spec: Arc<ChainSpec>) -> Self {
         assert!(chain_spec.is_optimism(), "optimism consensus only valid for optimism chains");
-        Self { chain_spec }
+        Self {
+            chain_spec,
+            clock: None,
+            gas_limit_cap: None,
+            per_tx_gas_cap: None,
+            sequencing_window: None,
+            checkpoints: HashMap::new(),
+            max_block_rlp_size: None,
+            #[cfg(feature = "metrics")]
+            metrics: Default::default(),
+        }
+    }
+
+    /// Enables rejecting post-Bedrock headers whose timestamp is more than `max_drift` seconds
+    /// ahead of the given clock.
+    ///
+    /// By default no clock is configured and post-Bedrock timestamps are not checked against the
+    /// current time, since that is the responsibility of the consensus layer.
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>, max_drift: u64) -> Self {
+        self.clock = Some((clock, max_drift));
+        self
+    }
+
+    /// Enables rejecting headers whose gas limit exceeds `cap`.
+    ///
+    /// By default the gas limit is not capped.
+    pub const fn with_gas_limit_cap(mut self, cap: u64) -> Self {
+        self.gas_limit_cap = Some(cap);
+        self
+    }
+
+    /// Enables rejecting blocks containing a transaction whose gas limit exceeds `cap`.
+    ///
+    /// By default the gas limit of transactions is not capped.
+    pub const fn with_per_tx_gas_cap(mut self, cap: u64) -> Self {
+        self.per_tx_gas_cap = Some(cap);
+        self
+    }
+
+    /// Enables rejecting blocks whose timestamp is more than `secs` seconds after the timestamp of
+    /// their L1 origin, decoded from the L1 attributes deposit.
+    ///
+    /// By default the sequencing window is not checked.
+    pub const fn with_sequencing_window(mut self, secs: u64) -> Self {
+        self.sequencing_window = Some(secs);
+        self
+    }
+
+    /// Pins the hashes of known-good blocks: headers at a checkpoint's number are rejected unless
+    /// their hash matches, e.g. during checkpoint sync.
+    pub fn with_checkpoints(mut self, checkpoints: HashMap<BlockNumber, B256>) -> Self {
+        self.checkpoints = checkpoints;
+        self
+    }
+
+    /// Enables rejecting blocks whose RLP encoding is longer than `bytes`.
+    ///
+    /// By default the size of blocks is not checked.
+    pub const fn with_max_block_rlp_size(mut self, bytes: usize) -> Self {
+        self.max_block_rlp_size = Some(bytes);
+        self
+    }
+
+    /// Validates the header's state root against a state root computed by the caller, e.g. from
+    /// an offline trie computation.
+    pub fn validate_state_root(
+        &self,
+        header: &Header,
+        computed_root: B256,
+    ) -> Result<(), ConsensusError> {
+        DeferredStateCheck { expected: header.state_root }.resolve(computed_root)
+    }
+
+    /// Runs the post-execution checks, see [`Consensus::validate_block_post_execution`], and
+    /// returns the check of the block's state root separately, to be resolved once the state root
+    /// has been computed.
+    pub fn validate_block_post_execution_deferred(
+        &self,
+        block: &BlockWithSenders,
+        input: PostExecutionInput<'_>,
+    ) -> (Result<(), ConsensusError>, DeferredStateCheck) {
+        (
+            self.validate_block_post_execution(block, input),
+            DeferredStateCheck { expected: block.state_root },
+        )
+    }
+
+    /// Runs the post-execution receipt checks, see [`validate_block_post_execution`], against the
+    /// header alone, for when the block body is not available.
+    pub fn validate_header_and_receipts(
+        &self,
+        header: &Header,
+        receipts: &[Receipt],
+    ) -> Result<(), ConsensusError> {
+        validation::validate_receipts_against_header(header, &self.chain_spec, receipts)
+    }
+
+    /// Returns `true` if both headers have the same parent, i.e. the same parent hash and number.
+    pub fn are_siblings(&self, a: &SealedHeader, b: &SealedHeader) -> bool {
+        a.parent_hash == b.parent_hash && a.number == b.number
+    }
+
+    /// Validates the header standalone and against its parent, which is looked up by hash with
+    /// `get_parent`, e.g. from storage.
+    ///
+    /// Returns [`ConsensusError::ParentUnknown`] if `get_parent` does not yield the parent.
+    pub fn validate_header_with_provider<F>(
+        &self,
+        header: &SealedHeader,
+        mut get_parent: F,
+    ) -> Result<(), ConsensusError>
+    where
+        F: FnMut(B256) -> Option<SealedHeader>,
+    {
+        self.validate_header(header)?;
+        let parent = get_parent(header.parent_hash)
+            .ok_or(ConsensusError::ParentUnknown { hash: header.parent_hash })?;
+        self.validate_header_against_parent(header, &parent)
+    }
+
+    /// Ensures that each header's parent hash is the hash of the previous header in the segment.
+    ///
+    /// Only the linkage is checked. On failure the index of the first header that does not link
+    /// to its predecessor is returned alongside the error.
+    pub fn validate_segment_linkage(
+        &self,
+        headers: &[SealedHeader],
+    ) -> Result<(), (usize, ConsensusError)> {
+        for (index, pair) in headers.windows(2).enumerate() {
+            let (parent, header) = (&pair[0], &pair[1]);
+            if header.parent_hash != parent.hash() {
+                return Err((
+                    index + 1,
+                    ConsensusError::ParentHashMismatch(
+                        GotExpected { got: header.parent_hash, expected: parent.hash() }.into(),
+                    ),
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs the standalone header checks, see [`Consensus::validate_header`], on all headers in
+    /// parallel.
+    ///
+    /// On failure the lowest index of a failing header is returned alongside its error, regardless
+    /// of the order in which the checks completed. The headers are not checked against their
+    /// parents, see [`Self::validate_segment_linkage`].
+    #[cfg(feature = "rayon")]
+    pub fn validate_headers_parallel(
+        &self,
+        headers: &[SealedHeader],
+    ) -> Result<(), (usize, ConsensusError)> {
+        headers
+            .par_iter()
+            .enumerate()
+            .find_map_first(|(index, header)| {
+                self.validate_header(header).err().map(|err| (index, err))
+            })
+            .map_or(Ok(()), Err)
+    }
+
+    /// Decodes the RLP encoded block and runs the pre-execution checks against it, see
+    /// [`Consensus::validate_block_pre_execution`].
+    ///
+    /// The block must span the whole input.
+    pub fn validate_encoded_block(&self, mut bytes: &[u8]) -> Result<(), ConsensusError> {
+        let block = Block::decode(&mut bytes).map_err(OpConsensusError::BlockDecode)?;
+        if !bytes.is_empty() {
+            return Err(OpConsensusError::BlockDecode(alloy_rlp::Error::UnexpectedLength).into())
+        }
+        self.validate_block_pre_execution(&block.seal_slow())
+    }
+
+    /// Returns the ordered list of pre-execution checks performed for blocks of the given OP
+    /// fork.
+    ///
+    /// Forks before Canyon, including non-OP forks, use the Bedrock checks.
+    pub const fn checks_for_fork(fork: Hardfork) -> &'static [CheckKind] {
+        match fork {
+            Hardfork::Canyon | Hardfork::Ecotone | Hardfork::Fjord => checks::CANYON_CHECKS,
+            _ => checks::BEDROCK_CHECKS,
+        }
+    }
+
+    /// Returns the latest OP fork that is active at the given timestamp.
+    ///
+    /// Falls back to [`Hardfork::Bedrock`] if no timestamp based fork is active.
+    fn active_fork(&self, timestamp: u64) -> Hardfork {
+        [Hardfork::Fjord, Hardfork::Ecotone, Hardfork::Canyon, Hardfork::Regolith]
+            .into_iter()
+            .find(|fork| self.chain_spec.is_fork_active_at_timestamp(*fork, timestamp))
+            .unwrap_or(Hardfork::Bedrock)
+    }
+
+    /// Runs `f`, recording its duration for the given validation phase if the `metrics` feature is
+    /// enabled.
+    #[inline]
+    fn timed<T>(&self, phase: ValidationPhase, f: impl FnOnce() -> T) -> T {
+        #[cfg(feature = "metrics")]
+        let start = Instant::now();
+        let res = f();
+        #[cfg(feature = "metrics")]
+        self.metrics.record(phase, start.elapsed());
+        #[cfg(not(feature = "metrics"))]
+        let _ = phase;
+        res
+    }
+
+    /// Runs a single pre-execution check against the block.
+    ///
+    /// [`CheckKind::BodyAgainstHeader`] already matches the withdrawals root against the body from
+    /// Shanghai, so the withdrawals checks don't compute the root again.
+    fn run_check(&self, check: CheckKind, block: &SealedBlock) -> Result<(), ConsensusError> {
+        match check {
+            CheckKind::BodyAgainstHeader => self.validate_body(block),
+            CheckKind::WithdrawalsRoot => self.ensure_no_withdrawals_before_shanghai(block),
+            CheckKind::EmptyWithdrawals => {
+                Ok(validate_no_body_withdrawals(block.withdrawals.as_ref())?)
+            }
+            CheckKind::UniqueDepositSourceHashes => {
+                Ok(ensure_unique_deposit_source_hashes(&block.body)?)
+            }
+        }
+    }
+
+    /// Validates the withdrawals related fields of the block according to the fork active at the
+    /// block's timestamp:
+    ///
+    /// - Before Shanghai, the block must not have withdrawals or a withdrawals root.
+    /// - From Shanghai, the withdrawals root must match the body's withdrawals.
+    /// - From Canyon, which activates Shanghai on OP chains, the withdrawals must additionally be
+    ///   empty, so the withdrawals root is the empty root.
+    pub fn validate_withdrawals(&self, block: &SealedBlock) -> Result<(), ConsensusError> {
+        match expected_withdrawals_root_kind(&self.chain_spec, block.timestamp) {
+            WithdrawalsRootKind::MustBeAbsent | WithdrawalsRootKind::RequiredPresent => {
+                self.validate_withdrawals_root(block)
+            }
+            WithdrawalsRootKind::MustBeEmpty => {
+                self.validate_withdrawals_root(block)?;
+                Ok(validate_no_body_withdrawals(block.withdrawals.as_ref())?)
+            }
+        }
+    }
+
+    /// Validates that the withdrawals root matches the body's withdrawals from Shanghai, and that
+    /// both are absent before.
+    fn validate_withdrawals_root(&self, block: &SealedBlock) -> Result<(), ConsensusError> {
+        if expected_withdrawals_root_kind(&self.chain_spec, block.timestamp) ==
+            WithdrawalsRootKind::MustBeAbsent
+        {
+            return self.ensure_no_withdrawals_before_shanghai(block)
+        }
+
+        let withdrawals =
+            block.withdrawals.as_ref().ok_or(ConsensusError::BodyWithdrawalsMissing)?;
+        let header_withdrawals_root =
+            block.withdrawals_root.ok_or(ConsensusError::WithdrawalsRootMissing)?;
+        let withdrawals_root = calculate_withdrawals_root(withdrawals);
+
+        if withdrawals_root != header_withdrawals_root {
+            return Err(ConsensusError::BodyWithdrawalsRootDiff(
+                GotExpected { got: withdrawals_root, expected: header_withdrawals_root }.into(),
+            ))
+        }
+
+        Ok(())
+    }
+
+    /// Validates the block's body against its header.
+    ///
+    /// Before Bedrock this is [`validate_block_pre_execution`]. From Bedrock on there are no
+    /// ommers, so the header's ommers root is compared against the empty root up front instead of
+    /// computing the root of the body's ommers, followed by the remaining checks of
+    /// [`validate_block_pre_execution`].
+    fn validate_body(&self, block: &SealedBlock) -> Result<(), ConsensusError> {
+        if !self.chain_spec.is_bedrock_active_at_block(block.number) {
+            return validate_block_pre_execution(block, &self.chain_spec)
+        }
+
+        ensure_empty_ommers_root(&block.header)?;
+        if !block.ommers.is_empty() {
+            return Err(ConsensusError::BodyOmmersHashDiff(
+                GotExpected {
+                    got: calculate_ommers_root(&block.ommers),
+                    expected: block.ommers_hash,
+                }
+                .into(),
+            ))
+        }
+
+        if let Err(error) = block.ensure_transaction_root_valid() {
+            return Err(ConsensusError::BodyTransactionRootDiff(error.into()))
+        }
+        if self.chain_spec.is_shanghai_active_at_timestamp(block.timestamp) {
+            validate_shanghai_withdrawals(block)?;
+        }
+        if self.chain_spec.is_cancun_active_at_timestamp(block.timestamp) {
+            validate_cancun_gas(block)?;
+        }
+        if self.chain_spec.is_prague_active_at_timestamp(block.timestamp) {
+            validate_prague_requests(block)?;
+        }
+        Ok(())
+    }
+
+    /// Ensures the block has neither withdrawals nor a withdrawals root before Shanghai.
+    fn ensure_no_withdrawals_before_shanghai(
+        &self,
+        block: &SealedBlock,
+    ) -> Result<(), ConsensusError> {
+        if expected_withdrawals_root_kind(&self.chain_spec, block.timestamp) ==
+            WithdrawalsRootKind::MustBeAbsent &&
+            (block.withdrawals.is_some() || block.withdrawals_root.is_some())
+        {
+            return Err(ConsensusError::WithdrawalsRootUnexpected)
+        }
+        Ok(())
+    }
+
+    /// Validates the block's body against its header.
+    ///
+    /// On failure the error is wrapped with the block's number and hash. The [`Consensus`] path
+    /// returns the unwrapped error instead.
+    pub fn validate_body_against_header(
+        &self,
+        block: &SealedBlock,
+    ) -> Result<(), OpConsensusError> {
+        self.validate_body(block).map_err(|inner| OpConsensusError::BodyHeaderMismatch {
+            number: block.number,
+            hash: block.hash(),
+            inner: Box::new(inner),
+        })
+    }
+}
+
+/// Clocks are compared by identity, metrics are ignored.
+impl PartialEq for OptimismBeaconConsensus {
+    fn eq(&self, other: &Self) -> bool {
+        let clocks_eq = match (&self.clock, &other.clock) {
+            (Some((a, a_drift)), Some((b, b_drift))) => Arc::ptr_eq(a, b) && a_drift == b_drift,
+            (None, None) => true,
+            _ => false,
+        };
+        clocks_eq &&
+            self.chain_spec == other.chain_spec &&
+            self.gas_limit_cap == other.gas_limit_cap &&
+            self.per_tx_gas_cap == other.per_tx_gas_cap &&
+            self.sequencing_window == other.sequencing_window &&
+            self.checkpoints == other.checkpoints &&
+            self.max_block_rlp_size == other.max_block_rlp_size
     }
 }
 
+impl Eq for OptimismBeaconConsensus {}
+
 impl Consensus for OptimismBeaconConsensus {
     fn validate_header(&self, header: &SealedHeader) -> Result<(), ConsensusError> {
+        if let Some(&expected) = self.checkpoints.get(&header.number) {
+            if header.hash() != expected {
+                return Err(OpConsensusError::CheckpointMismatch {
+                    number: header.number,
+                    hash: GotExpected { got: header.hash(), expected },
+                }
+                .into())
+            }
+        }
+
         validate_header_gas(header)?;
-        validate_header_base_fee(header, &self.chain_spec)
+
+        if let Some(cap) = self.gas_limit_cap {
+            if header.gas_limit > cap {
+                return Err(OpConsensusError::GasLimitExceedsCap {
+                    gas_limit: header.gas_limit,
+                    cap,
+                }
+                .into())
+            }
+        }
+
+        // difficulty and nonce are unused post-Bedrock, the merge transition happened at Bedrock
+        if self.chain_spec.is_bedrock_active_at_block(header.number) {
+            ensure_zero_difficulty(header)?;
+            ensure_zero_nonce(header)?;
+        }
+
+        ensure_base_fee_present(&self.chain_spec, header)
     }
 
     fn validate_header_against_parent(
@@ -57,20 +484,22 @@ impl Consensus for OptimismBeaconConsensus {
         header: &SealedHeader,
         parent: &SealedHeader,
     ) -> Result<(), ConsensusError> {
-        validate_against_parent_hash_number(header, parent)?;
+        self.timed(ValidationPhase::HeaderAgainstParent, || {
+            validate_against_parent_hash_number(header, parent)?;
 
-        if self.chain_spec.is_bedrock_active_at_block(header.number) {
-            validate_against_parent_timestamp(header, parent)?;
-        }
+            if self.chain_spec.is_bedrock_active_at_block(header.number) {
+                validate_against_parent_timestamp(header, parent)?;
+            }
 
-        validate_against_parent_eip1559_base_fee(header, parent, &self.chain_spec)?;
+            validate_against_parent_eip1559_base_fee(header, parent, &self.chain_spec)?;
 
-        // ensure that the blob gas fields for this block
-        if self.chain_spec.is_cancun_active_at_timestamp(header.timestamp) {
-            validate_against_parent_4844(header, parent)?;
-        }
+            // ensure that the blob gas fields for this block
+            if self.chain_spec.is_cancun_active_at_timestamp(header.timestamp) {
+                validate_against_parent_4844(header, parent)?;
+            }
 
-        Ok(())
+            Ok(())
+        })
     }
 
     fn validate_header_with_total_difficulty(
@@ -82,9 +511,7 @@ impl Consensus for OptimismBeaconConsensus {
         let is_post_merge = self.chain_spec.is_bedrock_active_at_block(header.number);
 
         if is_post_merge {
-            if header.nonce != 0 {
-                return Err(ConsensusError::TheMergeNonceIsNotZero)
-            }
+            ensure_zero_nonce(header)?;
 
             if header.ommers_hash != EMPTY_OMMER_ROOT_HASH {
                 return Err(ConsensusError::TheMergeOmmerRootIsNotEmpty)
@@ -97,9 +524,24 @@ impl Consensus for OptimismBeaconConsensus {
             //
             // Block validation with respect to the parent should ensure that the block timestamp
             // is greater than its parent timestamp.
+            //
+            // Validators that want a sanity guard anyway can opt in by configuring a clock.
+            if let Some((clock, max_drift)) = &self.clock {
+                let present_timestamp = clock.now();
+                if header.timestamp > present_timestamp.saturating_add(*max_drift) {
+                    return Err(ConsensusError::TimestampIsInFuture {
+                        timestamp: header.timestamp,
+                        present_timestamp,
+                    })
+                }
+            }
 
             // validate header extradata for all networks post merge
             validate_header_extradata(header)?;
+            // the extra data is unused after the bedrock transition block
+            if !self.chain_spec.fork(Hardfork::Bedrock).transitions_at_block(header.number) {
+                ensure_empty_extra_data(header)?;
+            }
 
             // mixHash is used instead of difficulty inside EVM
             // https://eips.ethereum.org/EIPS/eip-4399#using-mixhash-field-instead-of-difficulty
@@ -120,7 +562,25 @@ impl Consensus for OptimismBeaconConsensus {
     }
 
     fn validate_block_pre_execution(&self, block: &SealedBlock) -> Result<(), ConsensusError> {
-        validate_block_pre_execution(block, &self.chain_spec)
+        self.timed(ValidationPhase::PreExecution, || {
+            if let Some(max) = self.max_block_rlp_size {
+                let size = block.length();
+                if size > max {
+                    return Err(OpConsensusError::BlockTooLarge { size, max }.into())
+                }
+            }
+            let fork = self.active_fork(block.timestamp);
+            for check in Self::checks_for_fork(fork) {
+                self.run_check(*check, block)?;
+            }
+            if let Some(cap) = self.per_tx_gas_cap {
+                ensure_tx_gas_limits_within(&block.body, cap)?;
+            }
+            if let Some(window) = self.sequencing_window {
+                ensure_within_sequencing_window(&block.header, &block.body, fork, window)?;
+            }
+            Ok(())
+        })
     }
 
     fn validate_block_post_execution(
@@ -128,6 +588,738 @@ impl Consensus for OptimismBeaconConsensus {
         block: &BlockWithSenders,
         input: PostExecutionInput<'_>,
     ) -> Result<(), ConsensusError> {
-        validate_block_post_execution(block, &self.chain_spec, input.receipts)
+        self.timed(ValidationPhase::PostExecution, || {
+            validate_block_post_execution(block, &self.chain_spec, input.receipts)
+        })
+    }
+}
+
+/// The state root check of a block, split off by
+/// [`OptimismBeaconConsensus::validate_block_post_execution_deferred`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[must_use = "the state root check must be resolved"]
+pub struct DeferredStateCheck {
+    /// The state root of the block's header.
+    expected: B256,
+}
+
+impl DeferredStateCheck {
+    /// Returns the state root of the block's header.
+    pub const fn expected(&self) -> B256 {
+        self.expected
+    }
+
+    /// Ensures the computed state root matches the block's header.
+    pub fn resolve(self, state_root: B256) -> Result<(), ConsensusError> {
+        if state_root != self.expected {
+            return Err(ConsensusError::BodyStateRootDiff(
+                GotExpected { got: state_root, expected: self.expected }.into(),
+            ))
+        }
+        Ok(())
+    }
+}
+
+/// A validation phase of [`OptimismBeaconConsensus`] whose duration is recorded.
+#[derive(Debug, Clone, Copy)]
+enum ValidationPhase {
+    /// Pre-execution block validation.
+    PreExecution,
+    /// Post-execution block validation.
+    PostExecution,
+    /// Header validation against the parent.
+    HeaderAgainstParent,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::{ChainSpecBuilder, BASE_MAINNET};
+    use reth_primitives::{
+        constants::{eip4844::DATA_GAS_PER_BLOB, EMPTY_WITHDRAWALS},
+        hex,
+        proofs::{calculate_receipt_root_optimism, calculate_transaction_root},
+        Address, Bytes, ForkCondition, Log, Signature, Transaction, TransactionSigned, TxDeposit,
+        TxEip4844, TxLegacy, TxType, Withdrawal, Withdrawals,
+    };
+
+    fn bedrock_consensus() -> OptimismBeaconConsensus {
+        OptimismBeaconConsensus::new(Arc::new(
+            ChainSpecBuilder::from(&*BASE_MAINNET).bedrock_activated().build(),
+        ))
+    }
+
+    #[test]
+    fn validate_header_rejects_nonzero_difficulty() {
+        let consensus = bedrock_consensus();
+
+        let header =
+            Header { difficulty: U256::from(1), base_fee_per_gas: Some(1), ..Default::default() }
+                .seal_slow();
+        assert_eq!(
+            consensus.validate_header(&header),
+            Err(ConsensusError::TheMergeDifficultyIsNotZero)
+        );
+
+        let header = Header { base_fee_per_gas: Some(1), ..Default::default() }.seal_slow();
+        assert_eq!(consensus.validate_header(&header), Ok(()));
+    }
+
+    #[test]
+    fn validate_header_rejects_nonzero_nonce() {
+        let consensus = bedrock_consensus();
+
+        let header =
+            Header { nonce: 1, base_fee_per_gas: Some(1), ..Default::default() }.seal_slow();
+        assert_eq!(consensus.validate_header(&header), Err(ConsensusError::TheMergeNonceIsNotZero));
+    }
+
+    #[test]
+    fn validate_header_gas_limit_cap() {
+        let header = |gas_limit| {
+            Header { gas_limit, base_fee_per_gas: Some(1), ..Default::default() }.seal_slow()
+        };
+
+        // no cap configured
+        assert_eq!(bedrock_consensus().validate_header(&header(u64::MAX)), Ok(()));
+
+        let consensus = bedrock_consensus().with_gas_limit_cap(30_000_000);
+        assert_eq!(consensus.validate_header(&header(30_000_000)), Ok(()));
+        assert_eq!(
+            consensus.validate_header(&header(30_000_001)),
+            Err(OpConsensusError::GasLimitExceedsCap { gas_limit: 30_000_001, cap: 30_000_000 }
+                .into())
+        );
+    }
+
+    #[test]
+    fn checkpoints() {
+        let header =
+            Header { number: 5, base_fee_per_gas: Some(1), ..Default::default() }.seal_slow();
+        let other =
+            Header { number: 6, base_fee_per_gas: Some(1), ..Default::default() }.seal_slow();
+
+        let consensus = bedrock_consensus().with_checkpoints(HashMap::from([(5, header.hash())]));
+        assert_eq!(consensus.validate_header(&header), Ok(()));
+        assert_eq!(consensus.validate_header(&other), Ok(()));
+
+        let wrong = B256::with_last_byte(1);
+        let consensus = bedrock_consensus().with_checkpoints(HashMap::from([(5, wrong)]));
+        let err = consensus.validate_header(&header).unwrap_err();
+        assert_eq!(
+            err,
+            OpConsensusError::CheckpointMismatch {
+                number: 5,
+                hash: GotExpected { got: header.hash(), expected: wrong }
+            }
+            .into()
+        );
+        assert!(matches!(
+            err.as_custom::<OpConsensusError>(),
+            Some(OpConsensusError::CheckpointMismatch { number: 5, .. })
+        ));
+    }
+
+    #[test]
+    fn per_tx_gas_cap() {
+        let tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy { gas_limit: 1_000_000, ..Default::default() }),
+            Signature::default(),
+        );
+        let body = vec![tx];
+        let block = SealedBlock {
+            header: Header {
+                transactions_root: calculate_transaction_root(&body),
+                ..Default::default()
+            }
+            .seal_slow(),
+            body,
+            ..Default::default()
+        };
+
+        // no cap configured
+        assert_eq!(bedrock_consensus().validate_block_pre_execution(&block), Ok(()));
+
+        let consensus = bedrock_consensus().with_per_tx_gas_cap(1_000_000);
+        assert_eq!(consensus.validate_block_pre_execution(&block), Ok(()));
+
+        let consensus = bedrock_consensus().with_per_tx_gas_cap(999_999);
+        assert_eq!(
+            consensus.validate_block_pre_execution(&block),
+            Err(OpConsensusError::TransactionGasLimitExceedsCap {
+                index: 0,
+                gas_limit: 1_000_000,
+                cap: 999_999
+            }
+            .into())
+        );
+    }
+
+    #[test]
+    fn max_block_rlp_size() {
+        let body = vec![TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy { input: vec![0; 1024].into(), ..Default::default() }),
+            Signature::default(),
+        )];
+        let block = SealedBlock {
+            header: Header {
+                transactions_root: calculate_transaction_root(&body),
+                ..Default::default()
+            }
+            .seal_slow(),
+            body,
+            ..Default::default()
+        };
+        let size = block.length();
+
+        // no maximum configured
+        assert_eq!(bedrock_consensus().validate_block_pre_execution(&block), Ok(()));
+
+        let consensus = bedrock_consensus().with_max_block_rlp_size(size);
+        assert_eq!(consensus.validate_block_pre_execution(&block), Ok(()));
+
+        let consensus = bedrock_consensus().with_max_block_rlp_size(size - 1);
+        assert_eq!(
+            consensus.validate_block_pre_execution(&block),
+            Err(OpConsensusError::BlockTooLarge { size, max: size - 1 }.into())
+        );
+    }
+
+    #[test]
+    fn sequencing_window() {
+        // pre-Ecotone L1 attributes calldata, the L1 origin timestamp is the second word
+        let mut input = hex!("015d8eb9").to_vec();
+        input.extend_from_slice(&[0; 8 * 32]);
+        input[4 + 63] = 10;
+        let deposit = TransactionSigned::from_transaction_and_signature(
+            Transaction::Deposit(TxDeposit {
+                source_hash: B256::with_last_byte(1),
+                input: input.into(),
+                ..Default::default()
+            }),
+            Signature::optimism_deposit_tx_signature(),
+        );
+        let block = |timestamp| {
+            let body = vec![deposit.clone()];
+            SealedBlock {
+                header: Header {
+                    timestamp,
+                    transactions_root: calculate_transaction_root(&body),
+                    ..Default::default()
+                }
+                .seal_slow(),
+                body,
+                ..Default::default()
+            }
+        };
+
+        // no window configured
+        assert_eq!(bedrock_consensus().validate_block_pre_execution(&block(100)), Ok(()));
+
+        let consensus = bedrock_consensus().with_sequencing_window(5);
+        assert_eq!(consensus.validate_block_pre_execution(&block(15)), Ok(()));
+        assert_eq!(
+            consensus.validate_block_pre_execution(&block(16)),
+            Err(OpConsensusError::SequencingWindowExceeded {
+                timestamp: 16,
+                l1_timestamp: 10,
+                window: 5
+            }
+            .into())
+        );
+    }
+
+    #[test]
+    fn body_header_mismatch_preserves_inner_error() {
+        let consensus = bedrock_consensus();
+
+        let block = SealedBlock {
+            header: Header { ommers_hash: B256::ZERO, ..Default::default() }.seal_slow(),
+            ..Default::default()
+        };
+        let hash = block.hash();
+
+        let inner = ConsensusError::TheMergeOmmerRootIsNotEmpty;
+        assert_eq!(
+            consensus.validate_body_against_header(&block),
+            Err(OpConsensusError::BodyHeaderMismatch {
+                number: 0,
+                hash,
+                inner: Box::new(inner.clone()),
+            })
+        );
+        // the consensus path returns the error unchanged
+        assert_eq!(consensus.validate_block_pre_execution(&block), Err(inner));
+    }
+
+    #[test]
+    fn body_ommers_after_bedrock() {
+        let consensus = bedrock_consensus();
+
+        let block = SealedBlock::default();
+        assert_eq!(consensus.validate_body_against_header(&block), Ok(()));
+
+        // the header's ommers root is checked before the body
+        let block = SealedBlock {
+            header: Header { ommers_hash: B256::ZERO, ..Default::default() }.seal_slow(),
+            ommers: vec![Header::default()],
+            ..Default::default()
+        };
+        assert_eq!(
+            consensus.validate_block_pre_execution(&block),
+            Err(ConsensusError::TheMergeOmmerRootIsNotEmpty)
+        );
+
+        let ommers = vec![Header::default()];
+        let block = SealedBlock { ommers: ommers.clone(), ..Default::default() };
+        assert_eq!(
+            consensus.validate_block_pre_execution(&block),
+            Err(ConsensusError::BodyOmmersHashDiff(
+                GotExpected {
+                    got: calculate_ommers_root(&ommers),
+                    expected: EMPTY_OMMER_ROOT_HASH
+                }
+                .into()
+            ))
+        );
+    }
+
+    #[test]
+    fn blob_gas_used_against_body() {
+        let consensus = OptimismBeaconConsensus::new(Arc::new(
+            ChainSpecBuilder::from(&*BASE_MAINNET).ecotone_activated().build(),
+        ));
+
+        let blob_tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Eip4844(TxEip4844 {
+                blob_versioned_hashes: vec![B256::ZERO; 2],
+                ..Default::default()
+            }),
+            Signature::default(),
+        );
+        let block = |blob_gas_used| SealedBlock {
+            header: Header {
+                transactions_root: calculate_transaction_root(&[blob_tx.clone()]),
+                withdrawals_root: Some(EMPTY_WITHDRAWALS),
+                blob_gas_used: Some(blob_gas_used),
+                ..Default::default()
+            }
+            .seal_slow(),
+            body: vec![blob_tx.clone()],
+            withdrawals: Some(Withdrawals::default()),
+            ..Default::default()
+        };
+
+        assert_eq!(consensus.validate_block_pre_execution(&block(2 * DATA_GAS_PER_BLOB)), Ok(()));
+        assert_eq!(
+            consensus.validate_block_pre_execution(&block(DATA_GAS_PER_BLOB)),
+            Err(ConsensusError::BlobGasUsedDiff(GotExpected {
+                got: 2 * DATA_GAS_PER_BLOB,
+                expected: DATA_GAS_PER_BLOB
+            }))
+        );
+    }
+
+    #[test]
+    fn header_and_receipts() {
+        let consensus = bedrock_consensus();
+        let receipts = (1..=3u64)
+            .map(|i| Receipt {
+                tx_type: TxType::Eip1559,
+                success: true,
+                cumulative_gas_used: 21_000 * i,
+                logs: vec![Log::default()],
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+        let with_bloom = receipts.iter().cloned().map(Receipt::with_bloom).collect::<Vec<_>>();
+        let header = Header {
+            receipts_root: calculate_receipt_root_optimism(&with_bloom, &consensus.chain_spec, 0),
+            logs_bloom: with_bloom[0].bloom,
+            gas_used: 63_000,
+            ..Default::default()
+        };
+        assert_eq!(consensus.validate_header_and_receipts(&header, &receipts), Ok(()));
+
+        let mut other = receipts;
+        other[2].logs[0].address = Address::with_last_byte(1);
+        let got = calculate_receipt_root_optimism(
+            &other.iter().cloned().map(Receipt::with_bloom).collect::<Vec<_>>(),
+            &consensus.chain_spec,
+            0,
+        );
+        assert_eq!(
+            consensus.validate_header_and_receipts(&header, &other),
+            Err(ConsensusError::BodyReceiptRootDiff(
+                GotExpected { got, expected: header.receipts_root }.into()
+            ))
+        );
+    }
+
+    #[test]
+    fn siblings() {
+        let consensus = bedrock_consensus();
+        let header = |number, parent_hash, timestamp| {
+            Header { number, parent_hash, timestamp, ..Default::default() }.seal_slow()
+        };
+        let parent = B256::with_last_byte(1);
+
+        let a = header(1, parent, 1);
+        assert!(consensus.are_siblings(&a, &header(1, parent, 2)));
+        assert!(!consensus.are_siblings(&a, &header(1, B256::with_last_byte(2), 1)));
+        assert!(!consensus.are_siblings(&a, &header(2, parent, 1)));
+    }
+
+    #[test]
+    fn header_with_provider() {
+        let consensus = bedrock_consensus();
+        let parent =
+            Header { number: 1, base_fee_per_gas: Some(1), ..Default::default() }.seal_slow();
+        let header = Header {
+            number: 1,
+            parent_hash: parent.hash(),
+            base_fee_per_gas: Some(1),
+            ..Default::default()
+        }
+        .seal_slow();
+
+        assert_eq!(
+            consensus.validate_header_with_provider(&header, |_| None),
+            Err(ConsensusError::ParentUnknown { hash: parent.hash() })
+        );
+        assert_eq!(
+            consensus.validate_header_with_provider(&header, |hash| {
+                (hash == parent.hash()).then(|| parent.clone())
+            }),
+            Err(ConsensusError::ParentBlockNumberMismatch {
+                parent_block_number: 1,
+                block_number: 1
+            })
+        );
+    }
+
+    #[test]
+    fn segment_linkage() {
+        let consensus = bedrock_consensus();
+
+        let mut headers = Vec::new();
+        let mut parent_hash = B256::ZERO;
+        for number in 0..4 {
+            let header = Header { number, parent_hash, ..Default::default() }.seal_slow();
+            parent_hash = header.hash();
+            headers.push(header);
+        }
+        assert_eq!(consensus.validate_segment_linkage(&headers), Ok(()));
+        assert_eq!(consensus.validate_segment_linkage(&[]), Ok(()));
+
+        headers[2] =
+            Header { number: 2, parent_hash: B256::with_last_byte(1), ..Default::default() }
+                .seal_slow();
+        let expected = headers[1].hash();
+        assert_eq!(
+            consensus.validate_segment_linkage(&headers),
+            Err((
+                2,
+                ConsensusError::ParentHashMismatch(
+                    GotExpected { got: B256::with_last_byte(1), expected }.into()
+                )
+            ))
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn validate_headers_parallel_reports_lowest_index() {
+        let consensus = bedrock_consensus();
+        let headers = (0..64u64)
+            .map(|number| {
+                let difficulty =
+                    if number == 37 || number == 51 { U256::from(1) } else { U256::ZERO };
+                Header { number, difficulty, base_fee_per_gas: Some(1), ..Default::default() }
+                    .seal_slow()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(consensus.validate_headers_parallel(&headers[..37]), Ok(()));
+        assert_eq!(
+            consensus.validate_headers_parallel(&headers),
+            Err((37, ConsensusError::TheMergeDifficultyIsNotZero))
+        );
+    }
+
+    #[test]
+    fn validate_encoded_block() {
+        let consensus = bedrock_consensus();
+
+        let encoded = alloy_rlp::encode(Block::default());
+        assert_eq!(consensus.validate_encoded_block(&encoded), Ok(()));
+
+        let mut trailing = encoded;
+        trailing.push(0x80);
+        assert_eq!(
+            consensus.validate_encoded_block(&trailing),
+            Err(OpConsensusError::BlockDecode(alloy_rlp::Error::UnexpectedLength).into())
+        );
+
+        let invalid = Block {
+            header: Header { ommers_hash: B256::ZERO, ..Default::default() },
+            ..Default::default()
+        };
+        let err = consensus.validate_encoded_block(&alloy_rlp::encode(&invalid)).unwrap_err();
+        assert_eq!(err, consensus.validate_block_pre_execution(&invalid.seal_slow()).unwrap_err());
+    }
+
+    #[test]
+    fn state_root_mismatch() {
+        let consensus = bedrock_consensus();
+        let header = Header { state_root: B256::with_last_byte(1), ..Default::default() };
+
+        assert_eq!(consensus.validate_state_root(&header, B256::with_last_byte(1)), Ok(()));
+        assert_eq!(
+            consensus.validate_state_root(&header, B256::with_last_byte(2)),
+            Err(ConsensusError::BodyStateRootDiff(
+                GotExpected { got: B256::with_last_byte(2), expected: B256::with_last_byte(1) }
+                    .into()
+            ))
+        );
+    }
+
+    #[test]
+    fn post_execution_deferred() {
+        let consensus = bedrock_consensus();
+        let state_root = B256::with_last_byte(1);
+        let block = BlockWithSenders {
+            block: Block {
+                header: Header { state_root, gas_used: 1, ..Default::default() },
+                ..Default::default()
+            },
+            senders: vec![],
+        };
+
+        // gas used doesn't match the empty receipts, the state root check is still returned
+        let (res, deferred) = consensus
+            .validate_block_post_execution_deferred(&block, PostExecutionInput::new(&[], &[]));
+        assert!(matches!(res, Err(ConsensusError::BlockGasUsed { .. })));
+        assert_eq!(deferred.expected(), state_root);
+        assert_eq!(deferred.resolve(state_root), Ok(()));
+        assert_eq!(
+            deferred.resolve(B256::ZERO),
+            Err(ConsensusError::BodyStateRootDiff(
+                GotExpected { got: B256::ZERO, expected: state_root }.into()
+            ))
+        );
+
+        let block = BlockWithSenders {
+            block: Block {
+                header: Header { state_root, ..Default::default() },
+                ..Default::default()
+            },
+            senders: vec![],
+        };
+        let (res, deferred) = consensus
+            .validate_block_post_execution_deferred(&block, PostExecutionInput::new(&[], &[]));
+        assert_eq!(res, Ok(()));
+        assert_eq!(deferred.resolve(state_root), Ok(()));
+    }
+
+    #[test]
+    fn validate_withdrawals_per_fork() {
+        let block_with_withdrawals = |withdrawals: Option<Withdrawals>| SealedBlock {
+            header: Header {
+                withdrawals_root: withdrawals.as_ref().map(|w| calculate_withdrawals_root(w)),
+                ..Default::default()
+            }
+            .seal_slow(),
+            withdrawals,
+            ..Default::default()
+        };
+        let empty = block_with_withdrawals(Some(Withdrawals::default()));
+        let non_empty = block_with_withdrawals(Some(Withdrawals::new(vec![Withdrawal::default()])));
+
+        // pre-shanghai
+        let consensus = bedrock_consensus();
+        assert_eq!(consensus.validate_withdrawals(&block_with_withdrawals(None)), Ok(()));
+        assert_eq!(
+            consensus.validate_withdrawals(&empty),
+            Err(ConsensusError::WithdrawalsRootUnexpected)
+        );
+
+        // shanghai without canyon
+        let consensus = OptimismBeaconConsensus::new(Arc::new(
+            ChainSpecBuilder::from(&*BASE_MAINNET)
+                .bedrock_activated()
+                .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+                .build(),
+        ));
+        assert_eq!(consensus.validate_withdrawals(&non_empty), Ok(()));
+        assert_eq!(
+            consensus.validate_withdrawals(&block_with_withdrawals(None)),
+            Err(ConsensusError::BodyWithdrawalsMissing)
+        );
+
+        // canyon
+        let consensus = OptimismBeaconConsensus::new(Arc::new(
+            ChainSpecBuilder::from(&*BASE_MAINNET).canyon_activated().build(),
+        ));
+        assert_eq!(consensus.validate_withdrawals(&empty), Ok(()));
+        assert_eq!(
+            consensus.validate_withdrawals(&non_empty),
+            Err(OpConsensusError::UnexpectedBodyWithdrawals { count: 1 }.into())
+        );
+    }
+
+    #[test]
+    fn pre_execution_withdrawals() {
+        let block = |withdrawals, withdrawals_root| SealedBlock {
+            header: Header { withdrawals_root, ..Default::default() }.seal_slow(),
+            withdrawals: Some(withdrawals),
+            ..Default::default()
+        };
+        let non_empty = Withdrawals::new(vec![Withdrawal::default()]);
+        let root = calculate_withdrawals_root(&non_empty);
+
+        assert_eq!(
+            bedrock_consensus().validate_block_pre_execution(&block(Withdrawals::default(), None)),
+            Err(ConsensusError::WithdrawalsRootUnexpected)
+        );
+
+        let consensus = OptimismBeaconConsensus::new(Arc::new(
+            ChainSpecBuilder::from(&*BASE_MAINNET).canyon_activated().build(),
+        ));
+        assert_eq!(
+            consensus.validate_block_pre_execution(&block(
+                Withdrawals::default(),
+                Some(EMPTY_WITHDRAWALS)
+            )),
+            Ok(())
+        );
+        // the withdrawals root is matched against the body first
+        assert_eq!(
+            consensus
+                .validate_block_pre_execution(&block(non_empty.clone(), Some(EMPTY_WITHDRAWALS))),
+            Err(ConsensusError::BodyWithdrawalsRootDiff(
+                GotExpected { got: root, expected: EMPTY_WITHDRAWALS }.into()
+            ))
+        );
+        assert_eq!(
+            consensus.validate_block_pre_execution(&block(non_empty, Some(root))),
+            Err(OpConsensusError::UnexpectedBodyWithdrawals { count: 1 }.into())
+        );
+    }
+
+    #[test]
+    fn checks_per_fork() {
+        for fork in [Hardfork::Bedrock, Hardfork::Regolith] {
+            let checks = OptimismBeaconConsensus::checks_for_fork(fork);
+            assert!(checks.contains(&CheckKind::WithdrawalsRoot));
+            assert!(!checks.contains(&CheckKind::EmptyWithdrawals));
+        }
+        for fork in [Hardfork::Canyon, Hardfork::Ecotone, Hardfork::Fjord] {
+            let checks = OptimismBeaconConsensus::checks_for_fork(fork);
+            assert!(checks.contains(&CheckKind::EmptyWithdrawals));
+            assert_eq!(checks.first(), Some(&CheckKind::BodyAgainstHeader));
+        }
+
+        let consensus = OptimismBeaconConsensus::new(Arc::new(
+            ChainSpecBuilder::from(&*BASE_MAINNET).canyon_activated().build(),
+        ));
+        assert_eq!(consensus.active_fork(0), Hardfork::Canyon);
+        // regolith is active at genesis on base mainnet
+        assert_eq!(bedrock_consensus().active_fork(0), Hardfork::Regolith);
+    }
+
+    #[cfg(feature = "metrics")]
+    #[test]
+    fn records_validation_metrics() {
+        use metrics_util::debugging::DebuggingRecorder;
+
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        ::metrics::with_local_recorder(&recorder, || {
+            let consensus = bedrock_consensus();
+            let block = SealedBlock::default();
+            let _ = consensus.validate_block_pre_execution(&block);
+        });
+
+        let names = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, ..)| key.key().name().to_string())
+            .collect::<Vec<_>>();
+        for name in [
+            "optimism_consensus.pre_execution_duration_seconds",
+            "optimism_consensus.post_execution_duration_seconds",
+            "optimism_consensus.header_against_parent_duration_seconds",
+        ] {
+            assert!(names.iter().any(|n| n == name), "missing histogram {name}");
+        }
+    }
+
+    #[test]
+    fn extra_data_must_be_empty_after_bedrock() {
+        let chain_spec = ChainSpecBuilder::from(&*BASE_MAINNET)
+            .with_fork(Hardfork::Bedrock, ForkCondition::Block(1))
+            .build();
+        let consensus = OptimismBeaconConsensus::new(Arc::new(chain_spec));
+        let header = |number| Header {
+            number,
+            extra_data: Bytes::from_static(b"BEDROCK"),
+            ..Default::default()
+        };
+
+        // the bedrock transition block may carry extra data
+        assert_eq!(consensus.validate_header_with_total_difficulty(&header(1), U256::ZERO), Ok(()));
+        assert_eq!(
+            consensus.validate_header_with_total_difficulty(&header(2), U256::ZERO),
+            Err(OpConsensusError::NonEmptyExtraData { len: 7 }.into())
+        );
+        assert_eq!(
+            consensus.validate_header_with_total_difficulty(
+                &Header { number: 2, ..Default::default() },
+                U256::ZERO
+            ),
+            Ok(())
+        );
+    }
+
+    #[derive(Debug)]
+    struct MockClock(u64);
+
+    impl Clock for MockClock {
+        fn now(&self) -> u64 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn eq_compares_clock_identity() {
+        assert_eq!(bedrock_consensus(), bedrock_consensus());
+        assert_ne!(bedrock_consensus(), bedrock_consensus().with_gas_limit_cap(1));
+
+        let clock: Arc<dyn Clock> = Arc::new(MockClock(0));
+        let consensus = bedrock_consensus().with_clock(clock.clone(), 10);
+        assert_eq!(consensus, bedrock_consensus().with_clock(clock.clone(), 10));
+        assert_ne!(consensus, bedrock_consensus().with_clock(clock, 20));
+        assert_ne!(consensus, bedrock_consensus().with_clock(Arc::new(MockClock(0)), 10));
+        assert_ne!(consensus, bedrock_consensus());
+    }
+
+    #[test]
+    fn clock_rejects_future_timestamp() {
+        let header = Header { timestamp: 1_000, ..Default::default() };
+
+        // no clock configured, no check
+        let consensus = bedrock_consensus();
+        assert_eq!(consensus.validate_header_with_total_difficulty(&header, U256::ZERO), Ok(()));
+
+        let consensus = bedrock_consensus().with_clock(Arc::new(MockClock(900)), 100);
+        assert_eq!(consensus.validate_header_with_total_difficulty(&header, U256::ZERO), Ok(()));
+
+        let consensus = bedrock_consensus().with_clock(Arc::new(MockClock(899)), 100);
+        assert_eq!(
+            consensus.validate_header_with_total_difficulty(&header, U256::ZERO),
+            Err(ConsensusError::TimestampIsInFuture { timestamp: 1_000, present_timestamp: 899 })
+        );
     }
 }
@@ -0,0 +1,31 @@
+//! Optimism consensus validation metrics.
+
+use crate::ValidationPhase;
+use metrics::Histogram;
+use reth_metrics::Metrics;
+use std::time::Duration;
+
+/// Durations of the validation phases of
+/// [`OptimismBeaconConsensus`](crate::OptimismBeaconConsensus).
+#[derive(Clone, Metrics)]
+#[metrics(scope = "optimism_consensus")]
+pub(crate) struct ConsensusMetrics {
+    /// The number of seconds pre-execution block validation took.
+    pre_execution_duration_seconds: Histogram,
+    /// The number of seconds post-execution block validation took.
+    post_execution_duration_seconds: Histogram,
+    /// The number of seconds header validation against the parent took.
+    header_against_parent_duration_seconds: Histogram,
+}
+
+impl ConsensusMetrics {
+    /// Records the duration of the given validation phase.
+    pub(crate) fn record(&self, phase: ValidationPhase, duration: Duration) {
+        let histogram = match phase {
+            ValidationPhase::PreExecution => &self.pre_execution_duration_seconds,
+            ValidationPhase::PostExecution => &self.post_execution_duration_seconds,
+            ValidationPhase::HeaderAgainstParent => &self.header_against_parent_duration_seconds,
+        };
+        histogram.record(duration.as_secs_f64());
+    }
+}
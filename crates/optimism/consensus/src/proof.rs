@@ -0,0 +1,192 @@
+//! Optimism receipt trie proofs.
+
+use reth_chainspec::ChainSpec;
+use reth_primitives::{
+    proofs::calculate_receipt_root_optimism, Bloom, Bytes, GotExpected, Hardfork, Receipt,
+    ReceiptWithBloom, B256,
+};
+use reth_trie_common::{proof::verify_proof, Nibbles};
+
+/// Computes the receipt root of the given receipts at the given block `timestamp` and ensures it
+/// matches `expected`.
+pub fn ensure_receipts_root(
+    receipts: &[ReceiptWithBloom],
+    expected: B256,
+    chain_spec: &ChainSpec,
+    timestamp: u64,
+) -> Result<(), GotExpected<B256>> {
+    let got = calculate_receipt_root_optimism(receipts, chain_spec, timestamp);
+    if got != expected {
+        return Err(GotExpected { got, expected })
+    }
+    Ok(())
+}
+
+/// Verifies that `receipt` is included at `index` in the receipt trie with the given `root`.
+///
+/// The receipt is encoded the same way as in
+/// [`calculate_receipt_root_optimism`](reth_primitives::proofs::calculate_receipt_root_optimism):
+/// between Regolith and Canyon the deposit nonce is not part of the receipt leaf, which is why the
+/// block `timestamp` is required.
+///
+/// `proof` is the list of RLP-encoded trie nodes on the path from the root to the receipt leaf.
+pub fn verify_receipt_inclusion(
+    root: B256,
+    index: usize,
+    receipt: &ReceiptWithBloom,
+    proof: &[Bytes],
+    chain_spec: &ChainSpec,
+    timestamp: u64,
+) -> bool {
+    let key = Nibbles::unpack(alloy_rlp::encode_fixed_size(&index));
+    let value = encode_receipt_leaf(receipt, chain_spec, timestamp);
+    verify_proof(root, key, Some(value), proof).is_ok()
+}
+
+/// Returns an empty [`BloomAccumulator`].
+pub const fn bloom_accumulator() -> BloomAccumulator {
+    BloomAccumulator { bloom: Bloom::ZERO }
+}
+
+/// Computes the logs bloom of a block from its receipts one at a time, so the receipts don't have
+/// to be held in memory at once.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BloomAccumulator {
+    bloom: Bloom,
+}
+
+impl BloomAccumulator {
+    /// Adds the logs of the receipt to the bloom.
+    pub fn insert(&mut self, receipt: &Receipt) {
+        self.bloom |= receipt.bloom_slow();
+    }
+
+    /// Returns the logs bloom of all inserted receipts.
+    pub const fn finish(self) -> Bloom {
+        self.bloom
+    }
+}
+
+/// Encodes the receipt as it is stored in the receipt trie at the given timestamp.
+fn encode_receipt_leaf(
+    receipt: &ReceiptWithBloom,
+    chain_spec: &ChainSpec,
+    timestamp: u64,
+) -> Vec<u8> {
+    let mut buf = Vec::new();
+    if chain_spec.is_fork_active_at_timestamp(Hardfork::Regolith, timestamp) &&
+        !chain_spec.is_fork_active_at_timestamp(Hardfork::Canyon, timestamp)
+    {
+        let mut receipt = receipt.clone();
+        receipt.receipt.deposit_nonce = None;
+        receipt.encode_inner(&mut buf, false);
+    } else {
+        receipt.encode_inner(&mut buf, false);
+    }
+    buf
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::BASE_MAINNET;
+    use reth_primitives::{Address, Log, LogData, TxType};
+    use reth_trie_common::{proof::ProofRetainer, root::adjust_index_for_rlp, HashBuilder};
+
+    fn receipts() -> Vec<ReceiptWithBloom> {
+        (0..5u64)
+            .map(|i| {
+                Receipt {
+                    tx_type: if i == 0 { TxType::Deposit } else { TxType::Eip1559 },
+                    success: true,
+                    cumulative_gas_used: 21_000 * (i + 1),
+                    logs: vec![Log {
+                        address: Address::with_last_byte(i as u8),
+                        data: LogData::new_unchecked(vec![], Bytes::from(vec![i as u8])),
+                    }],
+                    deposit_nonce: (i == 0).then_some(1),
+                    deposit_receipt_version: (i == 0).then_some(1),
+                }
+                .with_bloom()
+            })
+            .collect()
+    }
+
+    /// Builds the receipt trie and returns the proof for the receipt at `target`.
+    fn receipt_proof(
+        receipts: &[ReceiptWithBloom],
+        chain_spec: &ChainSpec,
+        timestamp: u64,
+        target: usize,
+    ) -> (B256, Vec<Bytes>) {
+        let target = Nibbles::unpack(alloy_rlp::encode_fixed_size(&target));
+        let mut hb = HashBuilder::default().with_proof_retainer(ProofRetainer::from_iter([target]));
+        for i in 0..receipts.len() {
+            let index = adjust_index_for_rlp(i, receipts.len());
+            hb.add_leaf(
+                Nibbles::unpack(alloy_rlp::encode_fixed_size(&index)),
+                &encode_receipt_leaf(&receipts[index], chain_spec, timestamp),
+            );
+        }
+        let root = hb.root();
+        (root, hb.take_proofs().into_values().collect())
+    }
+
+    #[test]
+    fn verify_receipt_inclusion_proof() {
+        let chain_spec: &ChainSpec = &BASE_MAINNET;
+        let receipts = receipts();
+        let timestamp = chain_spec.fork(Hardfork::Canyon).as_timestamp().unwrap();
+        let (root, proof) = receipt_proof(&receipts, chain_spec, timestamp, 2);
+        assert_eq!(root, calculate_receipt_root_optimism(&receipts, chain_spec, timestamp));
+
+        assert!(verify_receipt_inclusion(root, 2, &receipts[2], &proof, chain_spec, timestamp));
+        // wrong index and wrong receipt
+        assert!(!verify_receipt_inclusion(root, 3, &receipts[2], &proof, chain_spec, timestamp));
+        assert!(!verify_receipt_inclusion(root, 2, &receipts[3], &proof, chain_spec, timestamp));
+    }
+
+    #[test]
+    fn verify_receipt_inclusion_tampered_proof() {
+        let chain_spec: &ChainSpec = &BASE_MAINNET;
+        let receipts = receipts();
+        let timestamp = chain_spec.fork(Hardfork::Canyon).as_timestamp().unwrap();
+        let (root, mut proof) = receipt_proof(&receipts, chain_spec, timestamp, 2);
+
+        let mut node = proof.last().unwrap().to_vec();
+        *node.last_mut().unwrap() ^= 1;
+        *proof.last_mut().unwrap() = node.into();
+        assert!(!verify_receipt_inclusion(root, 2, &receipts[2], &proof, chain_spec, timestamp));
+    }
+
+    #[test]
+    fn bloom_accumulator_matches_batch() {
+        let receipts = receipts();
+        let mut accumulator = bloom_accumulator();
+        for receipt in &receipts {
+            accumulator.insert(&receipt.receipt);
+        }
+
+        let expected = receipts.iter().fold(Bloom::ZERO, |bloom, r| bloom | r.bloom);
+        assert_ne!(expected, Bloom::ZERO);
+        assert_eq!(accumulator.finish(), expected);
+        assert_eq!(bloom_accumulator().finish(), Bloom::ZERO);
+    }
+
+    #[test]
+    fn receipts_root_matches() {
+        let chain_spec: &ChainSpec = &BASE_MAINNET;
+        let receipts = receipts();
+        let timestamp = chain_spec.fork(Hardfork::Canyon).as_timestamp().unwrap();
+        let (root, _) = receipt_proof(&receipts, chain_spec, timestamp, 0);
+
+        assert_eq!(ensure_receipts_root(&receipts, root, chain_spec, timestamp), Ok(()));
+        assert_eq!(
+            ensure_receipts_root(&receipts[1..], root, chain_spec, timestamp),
+            Err(GotExpected {
+                got: calculate_receipt_root_optimism(&receipts[1..], chain_spec, timestamp),
+                expected: root
+            })
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! Fixtures for testing against [`OptimismBeaconConsensus`].
+
+use crate::OptimismBeaconConsensus;
+use reth_chainspec::{ChainSpecBuilder, BASE_MAINNET};
+use reth_primitives::{
+    constants::OP_SYSTEM_TX_FROM_ADDR,
+    proofs::{calculate_receipt_root_optimism, calculate_transaction_root},
+    Header, Receipt, SealedBlock, Signature, Transaction, TransactionSigned, TxDeposit, TxType,
+    B256,
+};
+use std::sync::Arc;
+
+impl OptimismBeaconConsensus {
+    /// Returns a consensus for a Bedrock-active chain, a minimal block of that chain and the
+    /// receipts of executing it, which pass all validations.
+    ///
+    /// The block contains a single deposit transaction from the system address, its sender.
+    pub fn test_fixture() -> (Self, SealedBlock, Vec<Receipt>) {
+        let chain_spec = ChainSpecBuilder::from(&*BASE_MAINNET).bedrock_activated().build();
+
+        let deposit = TransactionSigned::from_transaction_and_signature(
+            Transaction::Deposit(TxDeposit {
+                source_hash: B256::with_last_byte(1),
+                from: OP_SYSTEM_TX_FROM_ADDR,
+                gas_limit: 1_000_000,
+                ..Default::default()
+            }),
+            Signature::optimism_deposit_tx_signature(),
+        );
+        let receipt = Receipt {
+            tx_type: TxType::Deposit,
+            success: true,
+            cumulative_gas_used: 21_000,
+            logs: vec![],
+            deposit_nonce: Some(0),
+            deposit_receipt_version: None,
+        };
+
+        let timestamp = 1;
+        let body = vec![deposit];
+        let header = Header {
+            number: 1,
+            timestamp,
+            gas_limit: 30_000_000,
+            gas_used: receipt.cumulative_gas_used,
+            base_fee_per_gas: Some(1),
+            transactions_root: calculate_transaction_root(&body),
+            receipts_root: calculate_receipt_root_optimism(
+                &[receipt.clone().with_bloom()],
+                &chain_spec,
+                timestamp,
+            ),
+            logs_bloom: receipt.bloom_slow(),
+            ..Default::default()
+        };
+        let block = SealedBlock { header: header.seal_slow(), body, ..Default::default() };
+
+        (Self::new(Arc::new(chain_spec)), block, vec![receipt])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_consensus::{Consensus, PostExecutionInput};
+    use reth_primitives::U256;
+
+    #[test]
+    fn fixture_passes_validation() {
+        let (consensus, block, receipts) = OptimismBeaconConsensus::test_fixture();
+
+        assert_eq!(consensus.validate_header(&block.header), Ok(()));
+        assert_eq!(
+            consensus.validate_header_with_total_difficulty(&block.header, U256::ZERO),
+            Ok(())
+        );
+        assert_eq!(consensus.validate_block_pre_execution(&block), Ok(()));
+
+        let block = block.unseal().with_senders_unchecked(vec![OP_SYSTEM_TX_FROM_ADDR]);
+        assert_eq!(
+            consensus
+                .validate_block_post_execution(&block, PostExecutionInput::new(&receipts, &[])),
+            Ok(())
+        );
+    }
+}
@@ -1,9 +1,518 @@
+//! Optimism block validation helpers.
+
+use crate::{DepositTxError, OpConsensusError};
 use reth_chainspec::ChainSpec;
 use reth_consensus::ConsensusError;
 use reth_primitives::{
-    gas_spent_by_transactions, proofs::calculate_receipt_root_optimism, BlockWithSenders, Bloom,
-    GotExpected, Receipt, B256,
+    constants::{eip4844::DATA_GAS_PER_BLOB, MINIMUM_GAS_LIMIT, OP_SYSTEM_TX_FROM_ADDR},
+    gas_spent_by_transactions, hex, keccak256,
+    proofs::calculate_receipt_root_optimism,
+    BlockWithSenders, Bloom, GotExpected, Hardfork, Header, Receipt, Requests, Signature,
+    Transaction, TransactionSigned, TxType, Withdrawals, B256, EMPTY_OMMER_ROOT_HASH, U256,
 };
+use std::collections::HashSet;
+
+/// Ensures the header's difficulty is zero.
+///
+/// Bedrock is the merge transition for OP chains, so all Bedrock-active blocks must have zero
+/// difficulty.
+#[inline]
+pub fn ensure_zero_difficulty(header: &Header) -> Result<(), ConsensusError> {
+    if header.difficulty != U256::ZERO {
+        return Err(ConsensusError::TheMergeDifficultyIsNotZero)
+    }
+    Ok(())
+}
+
+/// Ensures the header's nonce is zero.
+///
+/// Like the difficulty, the nonce is unused from Bedrock on and must be zero.
+#[inline]
+pub const fn ensure_zero_nonce(header: &Header) -> Result<(), ConsensusError> {
+    if header.nonce != 0 {
+        return Err(ConsensusError::TheMergeNonceIsNotZero)
+    }
+    Ok(())
+}
+
+/// Ensures the header has a base fee if London or Bedrock, which always builds on London, is active
+/// at its number.
+#[inline]
+pub fn ensure_base_fee_present(
+    chain_spec: &ChainSpec,
+    header: &Header,
+) -> Result<(), ConsensusError> {
+    let london = chain_spec.fork(Hardfork::London).active_at_block(header.number);
+    if (london || chain_spec.is_bedrock_active_at_block(header.number)) &&
+        header.base_fee_per_gas.is_none()
+    {
+        return Err(ConsensusError::BaseFeeMissing)
+    }
+    Ok(())
+}
+
+/// Ensures the header's ommers root is the root of an empty ommers list.
+///
+/// There are no ommers from Bedrock on, so the header can be compared against
+/// [`EMPTY_OMMER_ROOT_HASH`] without computing the root of the body's ommers.
+#[inline]
+pub fn ensure_empty_ommers_root(header: &Header) -> Result<(), ConsensusError> {
+    if header.ommers_hash != EMPTY_OMMER_ROOT_HASH {
+        return Err(ConsensusError::TheMergeOmmerRootIsNotEmpty)
+    }
+    Ok(())
+}
+
+/// Ensures the header's gas used does not exceed its gas limit.
+///
+/// Unlike [`validate_header_gas`](reth_consensus_common::validation::validate_header_gas), this
+/// works on an unsealed header and can be used as a standalone sanity check.
+#[inline]
+pub const fn ensure_gas_used_within_limit(header: &Header) -> Result<(), ConsensusError> {
+    if header.gas_used > header.gas_limit {
+        return Err(ConsensusError::HeaderGasUsedExceedsGasLimit {
+            gas_used: header.gas_used,
+            gas_limit: header.gas_limit,
+        })
+    }
+    Ok(())
+}
+
+/// Ensures the header's extra data is empty.
+///
+/// The extra data of post-Bedrock blocks is unused and must be empty, except for the Bedrock
+/// transition block itself.
+#[inline]
+pub fn ensure_empty_extra_data(header: &Header) -> Result<(), OpConsensusError> {
+    if !header.extra_data.is_empty() {
+        return Err(OpConsensusError::NonEmptyExtraData { len: header.extra_data.len() })
+    }
+    Ok(())
+}
+
+/// Ensures that no two deposit transactions in the block body share the same source hash.
+pub fn ensure_unique_deposit_source_hashes(
+    body: &[TransactionSigned],
+) -> Result<(), OpConsensusError> {
+    let mut seen = HashSet::new();
+    for (index, tx) in body.iter().enumerate() {
+        if let Some(source_hash) = tx.source_hash() {
+            if !seen.insert(source_hash) {
+                return Err(OpConsensusError::DuplicateDepositSourceHash { index, source_hash })
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Ensures that no transaction in the block body has a gas limit above `cap`.
+pub fn ensure_tx_gas_limits_within(
+    body: &[TransactionSigned],
+    cap: u64,
+) -> Result<(), OpConsensusError> {
+    if let Some((index, tx)) = body.iter().enumerate().find(|(_, tx)| tx.gas_limit() > cap) {
+        return Err(OpConsensusError::TransactionGasLimitExceedsCap {
+            index,
+            gas_limit: tx.gas_limit(),
+            cap,
+        })
+    }
+    Ok(())
+}
+
+/// Validates a single deposit transaction under the rules of the given fork:
+///
+/// - The source hash must be set.
+/// - The transaction must not be signed.
+/// - From Regolith on, it must not be a system transaction.
+///
+/// The mint and the value are not checked against each other. The mint is credited to the sender
+/// before the value is transferred, so whether the sender can afford the value is only known
+/// during execution. See [`validate_deposit_mint`] for which deposits may mint.
+pub fn validate_deposit_tx(tx: &TransactionSigned, fork: Hardfork) -> Result<(), OpConsensusError> {
+    let Transaction::Deposit(deposit) = &tx.transaction else {
+        return Err(DepositTxError::NotDeposit.into())
+    };
+
+    if deposit.source_hash.is_zero() {
+        return Err(DepositTxError::MissingSourceHash.into())
+    }
+    if tx.signature != Signature::optimism_deposit_tx_signature() {
+        return Err(DepositTxError::UnexpectedSignature.into())
+    }
+    if deposit.is_system_transaction && fork >= Hardfork::Regolith {
+        return Err(DepositTxError::SystemTransaction.into())
+    }
+    Ok(())
+}
+
+/// Ensures the system deposit, the first transaction of a block, is sent by the system address.
+///
+/// The system address has not changed across the supported forks, `_fork` is accepted so callers
+/// don't have to change once it does.
+pub fn validate_system_deposit_sender(
+    tx: &TransactionSigned,
+    _fork: Hardfork,
+) -> Result<(), OpConsensusError> {
+    let Transaction::Deposit(deposit) = &tx.transaction else {
+        return Err(DepositTxError::NotDeposit.into())
+    };
+    if deposit.from != OP_SYSTEM_TX_FROM_ADDR {
+        return Err(DepositTxError::UnexpectedSender {
+            got: deposit.from,
+            expected: OP_SYSTEM_TX_FROM_ADDR,
+        }
+        .into())
+    }
+    Ok(())
+}
+
+/// Computes the source hash of the L1 attributes deposit for the given L1 origin,
+/// `keccak256(bytes32(1) ++ keccak256(l1_hash ++ bytes32(sequence_number)))`.
+pub fn l1_attributes_source_hash(l1_info: &L1BlockInfo) -> B256 {
+    let mut buf = [0; 64];
+    buf[..32].copy_from_slice(l1_info.hash.as_slice());
+    buf[56..].copy_from_slice(&l1_info.sequence_number.to_be_bytes());
+    let deposit_id = keccak256(buf);
+
+    buf = [0; 64];
+    buf[31] = 1;
+    buf[32..].copy_from_slice(deposit_id.as_slice());
+    keccak256(buf)
+}
+
+/// Ensures the deposit only mints if it is a user deposit.
+///
+/// Only deposits initiated on L1 by users may mint ETH. The L1 attributes deposit of the given L1
+/// origin, identified by its source hash, and deposits sent by the system address must not mint.
+pub fn validate_deposit_mint(
+    tx: &TransactionSigned,
+    l1_info: &L1BlockInfo,
+) -> Result<(), OpConsensusError> {
+    let Transaction::Deposit(deposit) = &tx.transaction else {
+        return Err(DepositTxError::NotDeposit.into())
+    };
+    let Some(mint) = deposit.mint.filter(|mint| *mint != 0) else { return Ok(()) };
+    if deposit.from == OP_SYSTEM_TX_FROM_ADDR ||
+        deposit.source_hash == l1_attributes_source_hash(l1_info)
+    {
+        return Err(DepositTxError::UnexpectedMint { mint }.into())
+    }
+    Ok(())
+}
+
+/// Ensures that the block body starts with deposit transactions and that all deposit
+/// transactions form a prefix of the body.
+///
+/// The first transaction of every OP block is the L1 attributes deposit, so an empty body or a body
+/// starting with a regular transaction is rejected as well.
+pub fn ensure_deposits_first(body: &[TransactionSigned]) -> Result<(), OpConsensusError> {
+    if !body.first().is_some_and(|tx| tx.is_deposit()) {
+        return Err(OpConsensusError::MissingL1AttributesDeposit)
+    }
+
+    let deposits = body.iter().take_while(|tx| tx.is_deposit()).count();
+    if let Some(offset) = body[deposits..].iter().position(|tx| tx.is_deposit()) {
+        return Err(OpConsensusError::DepositAfterNonDeposit { index: deposits + offset })
+    }
+    Ok(())
+}
+
+/// Validates that the number based Bedrock activation of the header agrees with the timestamp
+/// based activation of the later OP forks.
+///
+/// Regolith, Canyon, Ecotone and Fjord all build on Bedrock, so a header whose timestamp activates
+/// any of them must be Bedrock-active by number as well.
+pub fn validate_bedrock_timestamp_consistency(
+    chain_spec: &ChainSpec,
+    header: &Header,
+) -> Result<(), OpConsensusError> {
+    if chain_spec.is_bedrock_active_at_block(header.number) {
+        return Ok(())
+    }
+
+    let active = [Hardfork::Regolith, Hardfork::Canyon, Hardfork::Ecotone, Hardfork::Fjord]
+        .into_iter()
+        .find(|fork| chain_spec.is_fork_active_at_timestamp(*fork, header.timestamp));
+    if let Some(fork) = active {
+        return Err(OpConsensusError::ForkBeforeBedrock {
+            fork,
+            number: header.number,
+            timestamp: header.timestamp,
+        })
+    }
+    Ok(())
+}
+
+/// Function selector of `setL1BlockValues`, the L1 attributes deposit call before Ecotone.
+const L1_INFO_BEDROCK_SELECTOR: [u8; 4] = hex!("015d8eb9");
+
+/// Function selector of `setL1BlockValuesEcotone`, the L1 attributes deposit call from Ecotone.
+const L1_INFO_ECOTONE_SELECTOR: [u8; 4] = hex!("440a5e20");
+
+/// The L1 origin of an OP block, as recorded by its L1 attributes deposit, see
+/// [`decode_l1_attributes`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L1BlockInfo {
+    /// The number of the L1 origin block.
+    pub number: u64,
+    /// The timestamp of the L1 origin block.
+    pub timestamp: u64,
+    /// The hash of the L1 origin block.
+    pub hash: B256,
+    /// The number of the L2 block within the epoch of the L1 origin.
+    pub sequence_number: u64,
+    /// The base fee of the L1 origin block.
+    pub base_fee: U256,
+    /// The blob base fee of the L1 origin block, from Ecotone.
+    pub blob_base_fee: Option<U256>,
+}
+
+/// Decodes the L1 origin info from the calldata of the L1 attributes deposit, the first
+/// transaction of every OP block.
+///
+/// Ecotone changed the calldata layout from 32 byte words to tightly packed fields. The Ecotone
+/// activation block itself still carries the pre-Ecotone layout, so from Ecotone on both layouts
+/// are accepted, while the Ecotone layout is rejected before.
+pub fn decode_l1_attributes(
+    tx: &TransactionSigned,
+    fork: Hardfork,
+) -> Result<L1BlockInfo, OpConsensusError> {
+    if !tx.is_deposit() {
+        return Err(DepositTxError::NotDeposit.into())
+    }
+
+    let input = tx.input();
+    let (selector, data) = input.split_at(input.len().min(4));
+    if selector == L1_INFO_BEDROCK_SELECTOR && data.len() == 8 * 32 {
+        Ok(L1BlockInfo {
+            number: word_u64(&data[..32])?,
+            timestamp: word_u64(&data[32..64])?,
+            base_fee: U256::from_be_slice(&data[64..96]),
+            hash: B256::from_slice(&data[96..128]),
+            sequence_number: word_u64(&data[128..160])?,
+            blob_base_fee: None,
+        })
+    } else if selector == L1_INFO_ECOTONE_SELECTOR && data.len() == 160 && fork >= Hardfork::Ecotone
+    {
+        Ok(L1BlockInfo {
+            sequence_number: packed_u64(&data[8..16]),
+            timestamp: packed_u64(&data[16..24]),
+            number: packed_u64(&data[24..32]),
+            base_fee: U256::from_be_slice(&data[32..64]),
+            blob_base_fee: Some(U256::from_be_slice(&data[64..96])),
+            hash: B256::from_slice(&data[96..128]),
+        })
+    } else {
+        Err(OpConsensusError::InvalidL1Attributes)
+    }
+}
+
+/// Decodes a `u64` from a big endian 32 byte word.
+fn word_u64(word: &[u8]) -> Result<u64, OpConsensusError> {
+    U256::from_be_slice(word).try_into().map_err(|_| OpConsensusError::InvalidL1Attributes)
+}
+
+/// Decodes a `u64` from 8 big endian bytes.
+fn packed_u64(bytes: &[u8]) -> u64 {
+    u64::from_be_bytes(bytes.try_into().expect("8 bytes"))
+}
+
+/// Ensures the header's parent beacon block root is consistent with the block's L1 attributes.
+///
+/// The L1 attributes don't commit to the beacon block root of the L1 origin, so only its presence
+/// can be checked: L1 attributes in the Ecotone layout, which carry the blob base fee, are only
+/// valid in blocks that activate Cancun and so must have a parent beacon block root. The Ecotone
+/// activation block still uses the pre-Ecotone layout, so the converse does not hold.
+pub fn validate_parent_beacon_root_consistency(
+    header: &Header,
+    l1_info: &L1BlockInfo,
+) -> Result<(), ConsensusError> {
+    if l1_info.blob_base_fee.is_some() && header.parent_beacon_block_root.is_none() {
+        return Err(ConsensusError::ParentBeaconBlockRootMissing)
+    }
+    Ok(())
+}
+
+/// Ensures the block's timestamp is at most `window` seconds after the timestamp of its L1 origin,
+/// as recorded by the L1 attributes deposit, the first transaction of the block body.
+///
+/// The L1 attributes are decoded under the rules of the given fork, see [`decode_l1_attributes`].
+pub fn ensure_within_sequencing_window(
+    header: &Header,
+    body: &[TransactionSigned],
+    fork: Hardfork,
+    window: u64,
+) -> Result<(), OpConsensusError> {
+    let tx = body
+        .first()
+        .filter(|tx| tx.is_deposit())
+        .ok_or(OpConsensusError::MissingL1AttributesDeposit)?;
+    let l1_timestamp = decode_l1_attributes(tx, fork)?.timestamp;
+    if header.timestamp > l1_timestamp.saturating_add(window) {
+        return Err(OpConsensusError::SequencingWindowExceeded {
+            timestamp: header.timestamp,
+            l1_timestamp,
+            window,
+        })
+    }
+    Ok(())
+}
+
+/// Validates that the number of blob versioned hashes of the blob transactions of the block body
+/// matches the number of blobs the header's blob gas used accounts for.
+pub fn validate_blob_versioned_hashes(
+    body: &[TransactionSigned],
+    header: &Header,
+) -> Result<(), OpConsensusError> {
+    let blob_gas_used = header.blob_gas_used.ok_or(OpConsensusError::BlobGasUsedMissing)?;
+    if blob_gas_used % DATA_GAS_PER_BLOB != 0 {
+        return Err(OpConsensusError::BlobGasUsedNotMultiple(blob_gas_used))
+    }
+
+    let blobs = body
+        .iter()
+        .filter_map(|tx| tx.as_eip4844())
+        .map(|tx| tx.blob_versioned_hashes.len() as u64)
+        .sum::<u64>();
+    let expected = blob_gas_used / DATA_GAS_PER_BLOB;
+    if blobs != expected {
+        return Err(OpConsensusError::BlobCountDiff(GotExpected { got: blobs, expected }))
+    }
+    Ok(())
+}
+
+/// What the withdrawals root of a header must look like, see [`expected_withdrawals_root_kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WithdrawalsRootKind {
+    /// The withdrawals root must be set and match the withdrawals of the body.
+    ///
+    /// OP chain specs activate Shanghai together with Canyon, so this only applies to chain specs
+    /// that activate Shanghai on its own.
+    RequiredPresent,
+    /// The withdrawals root must be the root of an empty withdrawals list.
+    MustBeEmpty,
+    /// The header must not have a withdrawals root.
+    MustBeAbsent,
+}
+
+/// Returns what the withdrawals root of a block at the given timestamp must look like:
+///
+/// - Before Shanghai there are no withdrawals.
+/// - From Shanghai the withdrawals root commits to the body's withdrawals.
+/// - From Canyon, which activates Shanghai on OP chains, withdrawals must be empty.
+pub fn expected_withdrawals_root_kind(
+    chain_spec: &ChainSpec,
+    timestamp: u64,
+) -> WithdrawalsRootKind {
+    if chain_spec.is_fork_active_at_timestamp(Hardfork::Canyon, timestamp) {
+        WithdrawalsRootKind::MustBeEmpty
+    } else if chain_spec.is_shanghai_active_at_timestamp(timestamp) {
+        WithdrawalsRootKind::RequiredPresent
+    } else {
+        WithdrawalsRootKind::MustBeAbsent
+    }
+}
+
+/// Ensures the body of a Canyon-active block carries no withdrawals.
+///
+/// OP chains have no withdrawals, so from Canyon the header commits to the empty withdrawals root
+/// and the body's withdrawals list must be empty. A missing list is left to the withdrawals root
+/// checks.
+pub fn validate_no_body_withdrawals(
+    withdrawals: Option<&Withdrawals>,
+) -> Result<(), OpConsensusError> {
+    match withdrawals {
+        Some(withdrawals) if !withdrawals.is_empty() => {
+            Err(OpConsensusError::UnexpectedBodyWithdrawals { count: withdrawals.len() })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Ensures the block carries no EIP-7685 requests before Prague, and that the header commits to its
+/// requests from Prague on:
+///
+/// - Before Prague, the body must not have any requests and the header must not have a requests
+///   root.
+/// - From Prague, the header must have a requests root.
+pub fn ensure_no_requests_before_fork(
+    chain_spec: &ChainSpec,
+    header: &Header,
+    requests: Option<&Requests>,
+) -> Result<(), ConsensusError> {
+    if chain_spec.is_prague_active_at_timestamp(header.timestamp) {
+        if header.requests_root.is_none() {
+            return Err(ConsensusError::RequestsRootMissing)
+        }
+        return Ok(())
+    }
+
+    if header.requests_root.is_some() {
+        return Err(ConsensusError::RequestsRootUnexpected)
+    }
+    if let Some(requests) = requests.filter(|requests| !requests.0.is_empty()) {
+        return Err(OpConsensusError::UnexpectedRequests { count: requests.0.len() }.into())
+    }
+    Ok(())
+}
+
+/// Returns the gas limit a child block can use given its parent's gas limit and the gas limit the
+/// block producer would like to use, under the rules of the given fork.
+///
+/// Before Bedrock the legacy L1 rules apply: the gas limit must change by strictly less than
+/// `parent_gas_limit / 1024` and can not go below [`MINIMUM_GAS_LIMIT`], so the desired gas limit
+/// is clamped to that range. From Bedrock on, the gas limit is set through the L1 system config and
+/// is not bounded relative to the parent, so the desired gas limit is returned as is.
+pub fn expected_child_gas_limit(parent_gas_limit: u64, desired: u64, fork: Hardfork) -> u64 {
+    if fork >= Hardfork::Bedrock {
+        return desired
+    }
+
+    let max_delta = (parent_gas_limit / 1024).saturating_sub(1);
+    let min = parent_gas_limit.saturating_sub(max_delta).max(MINIMUM_GAS_LIMIT);
+    let max = parent_gas_limit.saturating_add(max_delta).max(min);
+    desired.clamp(min, max)
+}
+
+/// Returns `true` if the header is the first block at which `fork` is active, i.e. the fork is
+/// inactive at the parent and active at the header.
+///
+/// Both block and timestamp based forks are supported.
+pub fn is_fork_activation_block(
+    chain_spec: &ChainSpec,
+    parent: &Header,
+    header: &Header,
+    fork: Hardfork,
+) -> bool {
+    let condition = chain_spec.fork(fork);
+    let is_active = |header: &Header| {
+        condition.active_at_block(header.number) || condition.active_at_timestamp(header.timestamp)
+    };
+    !is_active(parent) && is_active(header)
+}
+
+/// Returns the gas targets of the parent and the header, `gas_limit / elasticity_multiplier`,
+/// if they differ because a fork between the two changed the EIP-1559 elasticity multiplier.
+///
+/// Returns `None` if the elasticity multiplier is unchanged, even if the gas limit changed.
+pub fn gas_target_change(
+    chain_spec: &ChainSpec,
+    parent: &Header,
+    header: &Header,
+) -> Option<(u64, u64)> {
+    let parent_elasticity =
+        chain_spec.base_fee_params_at_timestamp(parent.timestamp).elasticity_multiplier;
+    let elasticity =
+        chain_spec.base_fee_params_at_timestamp(header.timestamp).elasticity_multiplier;
+    if parent_elasticity == elasticity {
+        return None
+    }
+
+    let parent_target = (parent.gas_limit as u128 / parent_elasticity) as u64;
+    let target = (header.gas_limit as u128 / elasticity) as u64;
+    (parent_target != target).then_some((parent_target, target))
+}
 
 /// Validate a block with regard to execution results:
 ///
@@ -14,17 +523,29 @@ pub fn validate_block_post_execution(
     chain_spec: &ChainSpec,
     receipts: &[Receipt],
 ) -> Result<(), ConsensusError> {
+    validate_receipts_against_header(&block.header, chain_spec, receipts)
+}
+
+/// Validates the receipts of a block against its header: the receipts root, logs bloom and gas
+/// used, without requiring the block body.
+pub(crate) fn validate_receipts_against_header(
+    header: &Header,
+    chain_spec: &ChainSpec,
+    receipts: &[Receipt],
+) -> Result<(), ConsensusError> {
+    validate_cumulative_gas_monotonic(receipts)?;
+
     // Before Byzantium, receipts contained state root that would mean that expensive
     // operation as hashing that is required for state root got calculated in every
     // transaction This was replaced with is_success flag.
     // See more about EIP here: https://eips.ethereum.org/EIPS/eip-658
-    if chain_spec.is_byzantium_active_at_block(block.header.number) {
+    if chain_spec.is_byzantium_active_at_block(header.number) {
         if let Err(error) = verify_receipts(
-            block.header.receipts_root,
-            block.header.logs_bloom,
+            header.receipts_root,
+            header.logs_bloom,
             receipts,
             chain_spec,
-            block.timestamp,
+            header.timestamp,
         ) {
             tracing::debug!(%error, ?receipts, "receipts verification failed");
             return Err(error)
@@ -34,9 +555,9 @@ pub fn validate_block_post_execution(
     // Check if gas used matches the value set in header.
     let cumulative_gas_used =
         receipts.last().map(|receipt| receipt.cumulative_gas_used).unwrap_or(0);
-    if block.gas_used != cumulative_gas_used {
+    if header.gas_used != cumulative_gas_used {
         return Err(ConsensusError::BlockGasUsed {
-            gas: GotExpected { got: cumulative_gas_used, expected: block.gas_used },
+            gas: GotExpected { got: cumulative_gas_used, expected: header.gas_used },
             gas_spent_by_tx: gas_spent_by_transactions(receipts),
         })
     }
@@ -44,6 +565,83 @@ pub fn validate_block_post_execution(
     Ok(())
 }
 
+/// Ensures that the cumulative gas used of the receipts of a block, deposit receipts included,
+/// never decreases.
+pub fn validate_cumulative_gas_monotonic(receipts: &[Receipt]) -> Result<(), OpConsensusError> {
+    for (index, pair) in receipts.windows(2).enumerate() {
+        let (previous, got) = (pair[0].cumulative_gas_used, pair[1].cumulative_gas_used);
+        if got < previous {
+            return Err(OpConsensusError::DecreasingCumulativeGas {
+                index: index + 1,
+                got,
+                previous,
+            })
+        }
+    }
+    Ok(())
+}
+
+/// Ensures that each receipt has the type of the transaction at the same index of the block body,
+/// e.g. deposit transactions have deposit receipts, and that there is a receipt per transaction.
+pub fn validate_receipt_types(
+    body: &[TransactionSigned],
+    receipts: &[Receipt],
+) -> Result<(), OpConsensusError> {
+    if body.len() != receipts.len() {
+        return Err(OpConsensusError::ReceiptCountDiff(GotExpected {
+            got: receipts.len(),
+            expected: body.len(),
+        }))
+    }
+    for (index, (tx, receipt)) in body.iter().zip(receipts).enumerate() {
+        if receipt.tx_type != tx.tx_type() {
+            return Err(OpConsensusError::ReceiptTypeMismatch {
+                index,
+                got: receipt.tx_type,
+                expected: tx.tx_type(),
+            })
+        }
+    }
+    Ok(())
+}
+
+/// Receipt statistics of a block, see [`validate_block_post_execution_counted`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReceiptStats {
+    /// Number of receipts of deposit transactions.
+    pub deposit_receipts: usize,
+    /// Number of receipts of user transactions.
+    pub user_receipts: usize,
+    /// Total number of logs over all receipts.
+    pub logs: usize,
+}
+
+impl ReceiptStats {
+    /// Collects the statistics of the given receipts.
+    pub fn from_receipts(receipts: &[Receipt]) -> Self {
+        receipts.iter().fold(Self::default(), |mut stats, receipt| {
+            if receipt.tx_type == TxType::Deposit {
+                stats.deposit_receipts += 1;
+            } else {
+                stats.user_receipts += 1;
+            }
+            stats.logs += receipt.logs.len();
+            stats
+        })
+    }
+}
+
+/// Same as [`validate_block_post_execution`], but also returns the [`ReceiptStats`] of the
+/// receipts on success.
+pub fn validate_block_post_execution_counted(
+    block: &BlockWithSenders,
+    chain_spec: &ChainSpec,
+    receipts: &[Receipt],
+) -> Result<ReceiptStats, ConsensusError> {
+    validate_block_post_execution(block, chain_spec, receipts)?;
+    Ok(ReceiptStats::from_receipts(receipts))
+}
+
 /// Verify the calculated receipts root against the expected receipts root.
 fn verify_receipts(
     expected_receipts_root: B256,
@@ -92,3 +690,690 @@ fn compare_receipts_root_and_logs_bloom(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::{BaseFeeParams, BaseFeeParamsKind, ChainSpecBuilder, BASE_MAINNET};
+    use reth_primitives::{
+        Address, Block, Bytes, ForkCondition, Log, LogData, Request, TxDeposit, TxEip4844,
+        TxLegacy, Withdrawal,
+    };
+
+    fn deposit_tx(source_hash: B256) -> TransactionSigned {
+        TransactionSigned::from_transaction_and_signature(
+            Transaction::Deposit(TxDeposit { source_hash, ..Default::default() }),
+            Signature::optimism_deposit_tx_signature(),
+        )
+    }
+
+    /// A pre-Ecotone L1 attributes deposit, taken from the `l1` tests of `reth-evm-optimism`.
+    fn bedrock_l1_attributes_tx() -> TransactionSigned {
+        TransactionSigned::decode_enveloped(&mut &hex!("7ef9015aa044bae9d41b8380d781187b426c6fe43df5fb2fb57bd4466ef6a701e1f01e015694deaddeaddeaddeaddeaddeaddeaddeaddead000194420000000000000000000000000000000000001580808408f0d18001b90104015d8eb900000000000000000000000000000000000000000000000000000000008057650000000000000000000000000000000000000000000000000000000063d96d10000000000000000000000000000000000000000000000000000000000009f35273d89754a1e0387b89520d989d3be9c37c1f32495a88faf1ea05c61121ab0d1900000000000000000000000000000000000000000000000000000000000000010000000000000000000000002d679b567db6187c0c8323fa982cfb88b74dbcc7000000000000000000000000000000000000000000000000000000000000083400000000000000000000000000000000000000000000000000000000000f4240")[..]).unwrap()
+    }
+
+    fn regular_tx() -> TransactionSigned {
+        TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy::default()),
+            Signature::default(),
+        )
+    }
+
+    #[test]
+    fn zero_difficulty() {
+        let header = Header::default();
+        assert_eq!(ensure_zero_difficulty(&header), Ok(()));
+
+        let header = Header { difficulty: U256::from(1), ..Default::default() };
+        assert_eq!(
+            ensure_zero_difficulty(&header),
+            Err(ConsensusError::TheMergeDifficultyIsNotZero)
+        );
+    }
+
+    #[test]
+    fn zero_nonce() {
+        assert_eq!(ensure_zero_nonce(&Header::default()), Ok(()));
+
+        let header = Header { nonce: 1, ..Default::default() };
+        assert_eq!(ensure_zero_nonce(&header), Err(ConsensusError::TheMergeNonceIsNotZero));
+    }
+
+    #[test]
+    fn gas_used_within_limit() {
+        let header = |gas_used| Header { gas_used, gas_limit: 30_000_000, ..Default::default() };
+
+        assert_eq!(ensure_gas_used_within_limit(&header(30_000_000)), Ok(()));
+        assert_eq!(
+            ensure_gas_used_within_limit(&header(30_000_001)),
+            Err(ConsensusError::HeaderGasUsedExceedsGasLimit {
+                gas_used: 30_000_001,
+                gas_limit: 30_000_000
+            })
+        );
+    }
+
+    #[test]
+    fn empty_extra_data() {
+        assert_eq!(ensure_empty_extra_data(&Header::default()), Ok(()));
+
+        let header = Header { extra_data: Bytes::from_static(b"reth"), ..Default::default() };
+        assert_eq!(
+            ensure_empty_extra_data(&header),
+            Err(OpConsensusError::NonEmptyExtraData { len: 4 })
+        );
+    }
+
+    #[test]
+    fn unique_deposit_source_hashes() {
+        let body = vec![deposit_tx(B256::with_last_byte(1)), deposit_tx(B256::with_last_byte(2))];
+        assert_eq!(ensure_unique_deposit_source_hashes(&body), Ok(()));
+
+        let body = vec![
+            deposit_tx(B256::with_last_byte(1)),
+            deposit_tx(B256::with_last_byte(2)),
+            deposit_tx(B256::with_last_byte(1)),
+        ];
+        assert_eq!(
+            ensure_unique_deposit_source_hashes(&body),
+            Err(OpConsensusError::DuplicateDepositSourceHash {
+                index: 2,
+                source_hash: B256::with_last_byte(1)
+            })
+        );
+    }
+
+    #[test]
+    fn tx_gas_limits_within() {
+        let tx = |gas_limit| {
+            TransactionSigned::from_transaction_and_signature(
+                Transaction::Legacy(TxLegacy { gas_limit, ..Default::default() }),
+                Signature::default(),
+            )
+        };
+        let body = vec![tx(21_000), tx(1_000_000), tx(30_000_000)];
+
+        assert_eq!(ensure_tx_gas_limits_within(&body, 30_000_000), Ok(()));
+        assert_eq!(
+            ensure_tx_gas_limits_within(&body, 500_000),
+            Err(OpConsensusError::TransactionGasLimitExceedsCap {
+                index: 1,
+                gas_limit: 1_000_000,
+                cap: 500_000
+            })
+        );
+    }
+
+    #[test]
+    fn deposit_tx_validation() {
+        let deposit = deposit_tx(B256::with_last_byte(1));
+        assert_eq!(validate_deposit_tx(&deposit, Hardfork::Ecotone), Ok(()));
+
+        let no_source_hash = deposit_tx(B256::ZERO);
+        assert_eq!(
+            validate_deposit_tx(&no_source_hash, Hardfork::Ecotone),
+            Err(DepositTxError::MissingSourceHash.into())
+        );
+
+        let signed = TransactionSigned::from_transaction_and_signature(
+            deposit.transaction.clone(),
+            Signature { r: U256::from(1), ..Signature::optimism_deposit_tx_signature() },
+        );
+        assert_eq!(
+            validate_deposit_tx(&signed, Hardfork::Ecotone),
+            Err(DepositTxError::UnexpectedSignature.into())
+        );
+
+        assert_eq!(
+            validate_deposit_tx(&regular_tx(), Hardfork::Ecotone),
+            Err(DepositTxError::NotDeposit.into())
+        );
+    }
+
+    #[test]
+    fn system_deposit_sender() {
+        let system_deposit = TransactionSigned::from_transaction_and_signature(
+            Transaction::Deposit(TxDeposit {
+                source_hash: B256::with_last_byte(1),
+                from: OP_SYSTEM_TX_FROM_ADDR,
+                ..Default::default()
+            }),
+            Signature::optimism_deposit_tx_signature(),
+        );
+        assert_eq!(validate_system_deposit_sender(&system_deposit, Hardfork::Ecotone), Ok(()));
+
+        let user_deposit = deposit_tx(B256::with_last_byte(1));
+        assert_eq!(
+            validate_system_deposit_sender(&user_deposit, Hardfork::Ecotone),
+            Err(DepositTxError::UnexpectedSender {
+                got: Address::ZERO,
+                expected: OP_SYSTEM_TX_FROM_ADDR
+            }
+            .into())
+        );
+        assert_eq!(
+            validate_system_deposit_sender(&regular_tx(), Hardfork::Ecotone),
+            Err(DepositTxError::NotDeposit.into())
+        );
+    }
+
+    #[test]
+    fn deposit_tx_system_transaction_per_fork() {
+        let system_tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Deposit(TxDeposit {
+                source_hash: B256::with_last_byte(1),
+                mint: Some(1),
+                is_system_transaction: true,
+                ..Default::default()
+            }),
+            Signature::optimism_deposit_tx_signature(),
+        );
+        assert_eq!(validate_deposit_tx(&system_tx, Hardfork::Bedrock), Ok(()));
+        assert_eq!(
+            validate_deposit_tx(&system_tx, Hardfork::Regolith),
+            Err(DepositTxError::SystemTransaction.into())
+        );
+    }
+
+    #[test]
+    fn deposits_first() {
+        let deposit = deposit_tx(B256::with_last_byte(1));
+        assert_eq!(ensure_deposits_first(&[deposit.clone(), regular_tx(), regular_tx()]), Ok(()));
+        assert_eq!(ensure_deposits_first(&[deposit.clone(), deposit.clone()]), Ok(()));
+
+        assert_eq!(ensure_deposits_first(&[]), Err(OpConsensusError::MissingL1AttributesDeposit));
+        assert_eq!(
+            ensure_deposits_first(&[regular_tx(), deposit.clone()]),
+            Err(OpConsensusError::MissingL1AttributesDeposit)
+        );
+        assert_eq!(
+            ensure_deposits_first(&[deposit.clone(), regular_tx(), deposit]),
+            Err(OpConsensusError::DepositAfterNonDeposit { index: 2 })
+        );
+    }
+
+    #[test]
+    fn bedrock_timestamp_consistency() {
+        let chain_spec = ChainSpecBuilder::from(&*BASE_MAINNET)
+            .with_fork(Hardfork::Bedrock, ForkCondition::Block(10))
+            .with_fork(Hardfork::Regolith, ForkCondition::Timestamp(1_000))
+            .build();
+        let header = |number, timestamp| Header { number, timestamp, ..Default::default() };
+
+        // pre-bedrock block before regolith, and bedrock block after regolith
+        assert_eq!(validate_bedrock_timestamp_consistency(&chain_spec, &header(9, 999)), Ok(()));
+        assert_eq!(validate_bedrock_timestamp_consistency(&chain_spec, &header(10, 1_000)), Ok(()));
+
+        // pre-bedrock block claiming regolith
+        assert_eq!(
+            validate_bedrock_timestamp_consistency(&chain_spec, &header(9, 1_000)),
+            Err(OpConsensusError::ForkBeforeBedrock {
+                fork: Hardfork::Regolith,
+                number: 9,
+                timestamp: 1_000
+            })
+        );
+    }
+
+    #[test]
+    fn blob_versioned_hashes() {
+        let blob_tx = |blobs| {
+            TransactionSigned::from_transaction_and_signature(
+                Transaction::Eip4844(TxEip4844 {
+                    blob_versioned_hashes: vec![B256::ZERO; blobs],
+                    ..Default::default()
+                }),
+                Signature::default(),
+            )
+        };
+        let body = vec![regular_tx(), blob_tx(2), blob_tx(1)];
+        let header = |blob_gas_used| Header { blob_gas_used, ..Default::default() };
+
+        assert_eq!(
+            validate_blob_versioned_hashes(&body, &header(Some(3 * DATA_GAS_PER_BLOB))),
+            Ok(())
+        );
+        assert_eq!(
+            validate_blob_versioned_hashes(&body, &header(Some(4 * DATA_GAS_PER_BLOB))),
+            Err(OpConsensusError::BlobCountDiff(GotExpected { got: 3, expected: 4 }))
+        );
+        assert_eq!(
+            validate_blob_versioned_hashes(&body, &header(Some(DATA_GAS_PER_BLOB + 1))),
+            Err(OpConsensusError::BlobGasUsedNotMultiple(DATA_GAS_PER_BLOB + 1))
+        );
+        assert_eq!(
+            validate_blob_versioned_hashes(&body, &header(None)),
+            Err(OpConsensusError::BlobGasUsedMissing)
+        );
+    }
+
+    #[test]
+    fn withdrawals_root_kind_across_forks() {
+        let chain_spec = ChainSpecBuilder::from(&*BASE_MAINNET)
+            .bedrock_activated()
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(10))
+            .with_fork(Hardfork::Canyon, ForkCondition::Timestamp(20))
+            .build();
+
+        assert_eq!(
+            expected_withdrawals_root_kind(&chain_spec, 9),
+            WithdrawalsRootKind::MustBeAbsent
+        );
+        assert_eq!(
+            expected_withdrawals_root_kind(&chain_spec, 10),
+            WithdrawalsRootKind::RequiredPresent
+        );
+        assert_eq!(
+            expected_withdrawals_root_kind(&chain_spec, 20),
+            WithdrawalsRootKind::MustBeEmpty
+        );
+
+        for chain_spec in [
+            ChainSpecBuilder::from(&*BASE_MAINNET).canyon_activated().build(),
+            ChainSpecBuilder::from(&*BASE_MAINNET).fjord_activated().build(),
+        ] {
+            assert_eq!(
+                expected_withdrawals_root_kind(&chain_spec, 0),
+                WithdrawalsRootKind::MustBeEmpty
+            );
+        }
+    }
+
+    #[test]
+    fn no_body_withdrawals_after_canyon() {
+        let chain_spec = ChainSpecBuilder::from(&*BASE_MAINNET)
+            .bedrock_activated()
+            .with_fork(Hardfork::Shanghai, ForkCondition::Timestamp(0))
+            .with_fork(Hardfork::Canyon, ForkCondition::Timestamp(0))
+            .build();
+        assert_eq!(
+            expected_withdrawals_root_kind(&chain_spec, 0),
+            WithdrawalsRootKind::MustBeEmpty
+        );
+
+        assert_eq!(validate_no_body_withdrawals(None), Ok(()));
+        assert_eq!(validate_no_body_withdrawals(Some(&Withdrawals::default())), Ok(()));
+
+        let withdrawals = Withdrawals::new(vec![Withdrawal::default(); 2]);
+        assert_eq!(
+            validate_no_body_withdrawals(Some(&withdrawals)),
+            Err(OpConsensusError::UnexpectedBodyWithdrawals { count: 2 })
+        );
+    }
+
+    #[test]
+    fn child_gas_limit_bounds() {
+        let parent = 30_000_000;
+        // 30_000_000 / 1024 = 29_296, the change must be strictly smaller
+        let max = parent + 29_295;
+        let min = parent - 29_295;
+
+        assert_eq!(expected_child_gas_limit(parent, max, Hardfork::London), max);
+        assert_eq!(expected_child_gas_limit(parent, max + 1, Hardfork::London), max);
+        assert_eq!(expected_child_gas_limit(parent, min, Hardfork::London), min);
+        assert_eq!(expected_child_gas_limit(parent, min - 1, Hardfork::London), min);
+        assert_eq!(expected_child_gas_limit(parent, parent, Hardfork::London), parent);
+
+        // never below the minimum gas limit
+        assert_eq!(
+            expected_child_gas_limit(MINIMUM_GAS_LIMIT, 0, Hardfork::London),
+            MINIMUM_GAS_LIMIT
+        );
+
+        // no relative bound from Bedrock on
+        assert_eq!(expected_child_gas_limit(parent, 2 * parent, Hardfork::Bedrock), 2 * parent);
+        assert_eq!(expected_child_gas_limit(parent, min - 1, Hardfork::Ecotone), min - 1);
+    }
+
+    #[test]
+    fn fork_activation_block() {
+        let chain_spec = ChainSpecBuilder::from(&*BASE_MAINNET)
+            .bedrock_activated()
+            .with_fork(Hardfork::Canyon, ForkCondition::Timestamp(10))
+            .with_fork(Hardfork::Ecotone, ForkCondition::Timestamp(20))
+            .build();
+        let header = |timestamp| Header { number: timestamp, timestamp, ..Default::default() };
+
+        for fork in [Hardfork::Canyon, Hardfork::Ecotone] {
+            let activation = chain_spec.fork(fork).as_timestamp().unwrap();
+            let (parent, child) = (header(activation - 1), header(activation));
+            assert!(is_fork_activation_block(&chain_spec, &parent, &child, fork));
+            assert!(!is_fork_activation_block(&chain_spec, &child, &header(activation + 1), fork));
+            assert!(!is_fork_activation_block(&chain_spec, &header(0), &parent, fork));
+        }
+        assert!(!is_fork_activation_block(&chain_spec, &header(9), &header(10), Hardfork::Ecotone));
+
+        // block based forks
+        let chain_spec = ChainSpecBuilder::from(&*BASE_MAINNET)
+            .with_fork(Hardfork::Bedrock, ForkCondition::Block(1))
+            .build();
+        assert!(is_fork_activation_block(&chain_spec, &header(0), &header(1), Hardfork::Bedrock));
+    }
+
+    #[test]
+    fn gas_target_change_across_canyon() {
+        let chain_spec = ChainSpecBuilder::from(&*BASE_MAINNET)
+            .bedrock_activated()
+            .with_fork(Hardfork::Canyon, ForkCondition::Timestamp(10))
+            .build();
+        let chain_spec = ChainSpec {
+            base_fee_params: BaseFeeParamsKind::Variable(
+                vec![
+                    (Hardfork::London, BaseFeeParams::new(50, 6)),
+                    (Hardfork::Canyon, BaseFeeParams::new(250, 10)),
+                ]
+                .into(),
+            ),
+            ..chain_spec
+        };
+        let header = |timestamp| Header { timestamp, gas_limit: 30_000_000, ..Default::default() };
+
+        assert_eq!(gas_target_change(&chain_spec, &header(8), &header(9)), None);
+        assert_eq!(
+            gas_target_change(&chain_spec, &header(9), &header(10)),
+            Some((5_000_000, 3_000_000))
+        );
+        assert_eq!(gas_target_change(&chain_spec, &header(10), &header(11)), None);
+
+        // base mainnet only changes the max change denominator at canyon
+        let chain_spec = ChainSpecBuilder::from(&*BASE_MAINNET)
+            .bedrock_activated()
+            .with_fork(Hardfork::Canyon, ForkCondition::Timestamp(10))
+            .build();
+        assert_eq!(gas_target_change(&chain_spec, &header(9), &header(10)), None);
+    }
+
+    #[test]
+    fn cumulative_gas_monotonic() {
+        let receipt = |tx_type, cumulative_gas_used| Receipt {
+            tx_type,
+            cumulative_gas_used,
+            deposit_nonce: (tx_type == TxType::Deposit).then_some(0),
+            ..Default::default()
+        };
+
+        let receipts = vec![
+            receipt(TxType::Deposit, 50_000),
+            receipt(TxType::Deposit, 50_000),
+            receipt(TxType::Eip1559, 71_000),
+        ];
+        assert_eq!(validate_cumulative_gas_monotonic(&receipts), Ok(()));
+        assert_eq!(validate_cumulative_gas_monotonic(&[]), Ok(()));
+
+        let receipts = vec![
+            receipt(TxType::Deposit, 50_000),
+            receipt(TxType::Deposit, 40_000),
+            receipt(TxType::Eip1559, 71_000),
+        ];
+        let err =
+            OpConsensusError::DecreasingCumulativeGas { index: 1, got: 40_000, previous: 50_000 };
+        assert_eq!(validate_cumulative_gas_monotonic(&receipts), Err(err.clone()));
+
+        let block = BlockWithSenders {
+            block: Block {
+                header: Header { gas_used: 71_000, ..Default::default() },
+                ..Default::default()
+            },
+            senders: vec![],
+        };
+        assert_eq!(
+            validate_block_post_execution(&block, &BASE_MAINNET, &receipts),
+            Err(err.into())
+        );
+    }
+
+    #[test]
+    fn receipt_types() {
+        let body = vec![deposit_tx(B256::with_last_byte(1)), regular_tx()];
+        let receipt = |tx_type| Receipt { tx_type, ..Default::default() };
+
+        assert_eq!(
+            validate_receipt_types(&body, &[receipt(TxType::Deposit), receipt(TxType::Legacy)]),
+            Ok(())
+        );
+        assert_eq!(
+            validate_receipt_types(&body, &[receipt(TxType::Deposit), receipt(TxType::Eip1559)]),
+            Err(OpConsensusError::ReceiptTypeMismatch {
+                index: 1,
+                got: TxType::Eip1559,
+                expected: TxType::Legacy
+            })
+        );
+        assert_eq!(
+            validate_receipt_types(&body, &[receipt(TxType::Deposit)]),
+            Err(OpConsensusError::ReceiptCountDiff(GotExpected { got: 1, expected: 2 }))
+        );
+    }
+
+    #[test]
+    fn post_execution_receipt_stats() {
+        let receipt = |tx_type, cumulative_gas_used, logs| Receipt {
+            tx_type,
+            success: true,
+            cumulative_gas_used,
+            logs: vec![
+                Log {
+                    address: Address::ZERO,
+                    data: LogData::new_unchecked(vec![], Bytes::new())
+                };
+                logs
+            ],
+            deposit_nonce: (tx_type == TxType::Deposit).then_some(0),
+            deposit_receipt_version: None,
+        };
+        let receipts = vec![
+            receipt(TxType::Deposit, 50_000, 0),
+            receipt(TxType::Eip1559, 71_000, 2),
+            receipt(TxType::Legacy, 92_000, 1),
+        ];
+
+        let receipts_with_bloom =
+            receipts.iter().cloned().map(Receipt::with_bloom).collect::<Vec<_>>();
+        let header = Header {
+            receipts_root: calculate_receipt_root_optimism(&receipts_with_bloom, &BASE_MAINNET, 0),
+            logs_bloom: receipts_with_bloom.iter().fold(Bloom::ZERO, |bloom, r| bloom | r.bloom),
+            gas_used: 92_000,
+            ..Default::default()
+        };
+        let block =
+            BlockWithSenders { block: Block { header, ..Default::default() }, senders: vec![] };
+
+        assert_eq!(
+            validate_block_post_execution_counted(&block, &BASE_MAINNET, &receipts),
+            Ok(ReceiptStats { deposit_receipts: 1, user_receipts: 2, logs: 3 })
+        );
+        assert!(
+            validate_block_post_execution_counted(&block, &BASE_MAINNET, &receipts[..2]).is_err()
+        );
+    }
+
+    #[test]
+    fn base_fee_present() {
+        let chain_spec = ChainSpecBuilder::from(&*BASE_MAINNET).bedrock_activated().build();
+        let header = Header { number: 1, base_fee_per_gas: Some(1), ..Default::default() };
+        assert_eq!(ensure_base_fee_present(&chain_spec, &header), Ok(()));
+        assert_eq!(
+            ensure_base_fee_present(&chain_spec, &Header { base_fee_per_gas: None, ..header }),
+            Err(ConsensusError::BaseFeeMissing)
+        );
+    }
+
+    #[test]
+    fn empty_ommers_root() {
+        assert_eq!(ensure_empty_ommers_root(&Header::default()), Ok(()));
+        assert_eq!(
+            ensure_empty_ommers_root(&Header { ommers_hash: B256::ZERO, ..Default::default() }),
+            Err(ConsensusError::TheMergeOmmerRootIsNotEmpty)
+        );
+    }
+
+    #[test]
+    fn requests_before_and_after_prague() {
+        let chain_spec = ChainSpecBuilder::from(&*BASE_MAINNET)
+            .bedrock_activated()
+            .with_fork(Hardfork::Prague, ForkCondition::Timestamp(10))
+            .build();
+        let requests = Requests(vec![Request::WithdrawalRequest(Default::default())]);
+        let header =
+            |timestamp, requests_root| Header { timestamp, requests_root, ..Default::default() };
+
+        // before prague
+        assert_eq!(ensure_no_requests_before_fork(&chain_spec, &header(9, None), None), Ok(()));
+        assert_eq!(
+            ensure_no_requests_before_fork(
+                &chain_spec,
+                &header(9, None),
+                Some(&Requests::default())
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            ensure_no_requests_before_fork(&chain_spec, &header(9, None), Some(&requests)),
+            Err(OpConsensusError::UnexpectedRequests { count: 1 }.into())
+        );
+        assert_eq!(
+            ensure_no_requests_before_fork(&chain_spec, &header(9, Some(B256::ZERO)), None),
+            Err(ConsensusError::RequestsRootUnexpected)
+        );
+
+        // prague
+        assert_eq!(
+            ensure_no_requests_before_fork(
+                &chain_spec,
+                &header(10, Some(B256::ZERO)),
+                Some(&requests)
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            ensure_no_requests_before_fork(&chain_spec, &header(10, None), Some(&requests)),
+            Err(ConsensusError::RequestsRootMissing)
+        );
+    }
+
+    #[test]
+    fn parent_beacon_root_consistency() {
+        let l1_info = |blob_base_fee| L1BlockInfo {
+            number: 1,
+            timestamp: 1,
+            hash: B256::with_last_byte(1),
+            sequence_number: 0,
+            base_fee: U256::from(1),
+            blob_base_fee,
+        };
+        let header =
+            |parent_beacon_block_root| Header { parent_beacon_block_root, ..Default::default() };
+
+        let ecotone = l1_info(Some(U256::from(1)));
+        assert_eq!(
+            validate_parent_beacon_root_consistency(&header(Some(B256::ZERO)), &ecotone),
+            Ok(())
+        );
+        assert_eq!(
+            validate_parent_beacon_root_consistency(&header(None), &ecotone),
+            Err(ConsensusError::ParentBeaconBlockRootMissing)
+        );
+
+        let bedrock = l1_info(None);
+        assert_eq!(validate_parent_beacon_root_consistency(&header(None), &bedrock), Ok(()));
+        assert_eq!(
+            validate_parent_beacon_root_consistency(&header(Some(B256::ZERO)), &bedrock),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn decode_l1_attributes_bedrock() {
+        let tx = bedrock_l1_attributes_tx();
+
+        let expected = L1BlockInfo {
+            number: 0x805765,
+            timestamp: 0x63d96d10,
+            hash: B256::from(hex!(
+                "73d89754a1e0387b89520d989d3be9c37c1f32495a88faf1ea05c61121ab0d19"
+            )),
+            sequence_number: 1,
+            base_fee: U256::from(652_114),
+            blob_base_fee: None,
+        };
+        assert_eq!(decode_l1_attributes(&tx, Hardfork::Bedrock), Ok(expected));
+        // the Ecotone activation block still uses the pre-Ecotone layout
+        assert_eq!(decode_l1_attributes(&tx, Hardfork::Ecotone), Ok(expected));
+    }
+
+    #[test]
+    fn deposit_mint() {
+        let tx = bedrock_l1_attributes_tx();
+        let l1_info = decode_l1_attributes(&tx, Hardfork::Bedrock).unwrap();
+        assert_eq!(tx.source_hash(), Some(l1_attributes_source_hash(&l1_info)));
+        assert_eq!(validate_deposit_mint(&tx, &l1_info), Ok(()));
+
+        let Transaction::Deposit(l1_attributes) = tx.transaction else { unreachable!() };
+        let signed = |deposit| {
+            TransactionSigned::from_transaction_and_signature(
+                Transaction::Deposit(deposit),
+                Signature::optimism_deposit_tx_signature(),
+            )
+        };
+
+        // the L1 attributes deposit must not mint
+        let minting = signed(TxDeposit { mint: Some(1), ..l1_attributes.clone() });
+        assert_eq!(
+            validate_deposit_mint(&minting, &l1_info),
+            Err(DepositTxError::UnexpectedMint { mint: 1 }.into())
+        );
+
+        // neither must other deposits sent by the system address
+        let system = signed(TxDeposit {
+            source_hash: B256::with_last_byte(1),
+            from: OP_SYSTEM_TX_FROM_ADDR,
+            mint: Some(1),
+            ..Default::default()
+        });
+        assert_eq!(
+            validate_deposit_mint(&system, &l1_info),
+            Err(DepositTxError::UnexpectedMint { mint: 1 }.into())
+        );
+
+        // user deposits may mint
+        let user = signed(TxDeposit {
+            source_hash: B256::with_last_byte(1),
+            from: Address::with_last_byte(1),
+            mint: Some(1),
+            ..l1_attributes
+        });
+        assert_eq!(validate_deposit_mint(&user, &l1_info), Ok(()));
+
+        assert_eq!(
+            validate_deposit_mint(&regular_tx(), &l1_info),
+            Err(DepositTxError::NotDeposit.into())
+        );
+    }
+
+    #[test]
+    fn decode_l1_attributes_ecotone() {
+        let tx = TransactionSigned::decode_enveloped(&mut &hex!("7ef8f8a0b84fa363879a2159e341c50a32da3ea0d21765b7bd43db37f2e5e04e8848b1ee94deaddeaddeaddeaddeaddeaddeaddeaddead00019442000000000000000000000000000000000000158080830f424080b8a4440a5e20000f42400000000000000000000000040000000065c41f680000000000a03f6b00000000000000000000000000000000000000000000000000000000000000080000000000000000000000000000000000000000000000000000000535f4d983dea59eac60478a64ecfdcde8571e611404295350de7ed4ccb404296c1a84ab7a00000000000000000000000073b4168cc87f35cc239200a20eb841cded23493b")[..]).unwrap();
+
+        assert_eq!(
+            decode_l1_attributes(&tx, Hardfork::Ecotone),
+            Ok(L1BlockInfo {
+                number: 0xa03f6b,
+                timestamp: 0x65c41f68,
+                hash: B256::from(hex!(
+                    "dea59eac60478a64ecfdcde8571e611404295350de7ed4ccb404296c1a84ab7a"
+                )),
+                sequence_number: 4,
+                base_fee: U256::from(8),
+                blob_base_fee: Some(U256::from(22_380_075_395u64)),
+            })
+        );
+        assert_eq!(
+            decode_l1_attributes(&tx, Hardfork::Canyon),
+            Err(OpConsensusError::InvalidL1Attributes)
+        );
+        assert_eq!(
+            decode_l1_attributes(&TransactionSigned::default(), Hardfork::Ecotone),
+            Err(DepositTxError::NotDeposit.into())
+        );
+    }
+}
@@ -1,35 +1,502 @@
 //! EIP-7685 requests.
 
 pub use alloy_consensus::Request;
-use alloy_eips::eip7685::{Decodable7685, Encodable7685};
+use alloy_eips::{
+    eip6110::DepositRequest,
+    eip7002::WITHDRAWAL_REQUEST_TYPE,
+    eip7251::ConsolidationRequest,
+    eip7685::{Decodable7685, Eip7685Error, Encodable7685},
+};
+use alloy_primitives::{b256, FixedBytes, B256};
 use alloy_rlp::{Decodable, Encodable};
+use core::fmt;
 use derive_more::{Deref, DerefMut, From, IntoIterator};
 use reth_codecs::{main_codec, Compact};
 use revm_primitives::Bytes;
+use sha2::{Digest, Sha256};
 
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::collections::{BTreeMap, BTreeSet};
+
+/// The EIP-7685 requests hash of a block without requests, the SHA-256 hash of empty input.
+pub const EMPTY_REQUESTS_HASH: B256 =
+    b256!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+
+/// Computes the EIP-7685 requests hash: the SHA-256 hash of the concatenated SHA-256 hashes of
+/// `type ++ data` of each request type present, see [`Requests::execution_request_chunks`].
+///
+/// Returns [`EMPTY_REQUESTS_HASH`] without hashing if there are no requests.
+pub fn compute_requests_hash(requests: &Requests) -> B256 {
+    if requests.is_empty() {
+        return EMPTY_REQUESTS_HASH
+    }
+
+    let mut hasher = Sha256::new();
+    for (ty, data) in requests.execution_request_chunks() {
+        let mut chunk_hasher = Sha256::new();
+        chunk_hasher.update([ty]);
+        chunk_hasher.update(&data);
+        hasher.update(chunk_hasher.finalize());
+    }
+    B256::from_slice(&hasher.finalize())
+}
 
 /// A list of EIP-7685 requests.
 #[main_codec]
 #[derive(Debug, Clone, PartialEq, Eq, Default, Hash, Deref, DerefMut, From, IntoIterator)]
 pub struct Requests(pub Vec<Request>);
 
-impl Encodable for Requests {
-    fn encode(&self, out: &mut dyn bytes::BufMut) {
-        let mut h = alloy_rlp::Header { list: true, payload_length: 0 };
+impl Requests {
+    /// Creates an empty list of requests with at least the specified capacity.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self(Vec::with_capacity(capacity))
+    }
+
+    /// Removes all requests, retaining the allocated capacity for reuse.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
 
-        let mut encoded = Vec::new();
+    /// Appends a request, keeping the list canonically ordered by request type.
+    ///
+    /// Fails without modifying the list if the request's type is lower than the type of the last
+    /// request.
+    pub fn push_typed(&mut self, req: Request) -> Result<(), OrderingViolation> {
+        let ty = req.request_type();
+        if let Some(last) = self.0.last().map(|last| last.request_type()) {
+            if ty < last {
+                return Err(OrderingViolation { ty, last })
+            }
+        }
+        self.0.push(req);
+        Ok(())
+    }
+
+    /// Returns the total length of the EIP-7685 encodings of all requests, i.e. without the RLP
+    /// headers of [`Encodable::length`].
+    pub fn encoded_byte_len(&self) -> usize {
+        self.0.iter().map(encoded_7685_len).sum()
+    }
+
+    /// Returns the distinct request types present that are not in `known`, in ascending order.
+    pub fn unknown_types(&self, known: &[u8]) -> Vec<u8> {
+        let mut types = self
+            .0
+            .iter()
+            .map(|req| req.request_type())
+            .filter(|ty| !known.contains(ty))
+            .collect::<Vec<_>>();
+        types.sort_unstable();
+        types.dedup();
+        types
+    }
+
+    /// Counts the requests per type, without allocating.
+    pub fn summary(&self) -> RequestsSummary {
+        let mut summary = RequestsSummary { total: self.0.len(), ..Default::default() };
         for req in &self.0 {
-            let encoded_req = req.encoded_7685();
-            h.payload_length += encoded_req.len();
-            encoded.push(Bytes::from(encoded_req));
+            if let Some(count) = summary.per_type.get_mut(req.request_type() as usize) {
+                *count += 1;
+            }
         }
+        summary
+    }
 
-        h.encode(out);
-        for req in encoded {
-            req.encode(out);
+    /// Returns an iterator over the EIP-7251 consolidation requests.
+    pub fn consolidation_requests(&self) -> impl Iterator<Item = &ConsolidationRequest> + '_ {
+        self.0.iter().filter_map(Request::as_consolidation_request)
+    }
+
+    /// Decodes a list of requests like [`Decodable::decode`], but decodes each entry with the
+    /// decoder registered for its type in `registry`.
+    ///
+    /// Fails with [`RequestsDecodeError::UnknownType`] if no decoder is registered for the type of
+    /// an entry, and with [`RequestsDecodeError::InvalidLength`] if the decoder does not consume
+    /// all of the entry's data.
+    pub fn decode_with_registry(
+        buf: &mut &[u8],
+        registry: &RequestTypeRegistry,
+    ) -> Result<Self, RequestsDecodeError> {
+        let entries = <Vec<Bytes> as Decodable>::decode(buf)?;
+        let mut requests = Vec::with_capacity(entries.len());
+        for (index, entry) in entries.iter().enumerate() {
+            let Some((&ty, mut data)) = entry.split_first() else {
+                return Err(alloy_rlp::Error::from(Eip7685Error::MissingType).into())
+            };
+            let decoder =
+                registry.decoder(ty).ok_or(RequestsDecodeError::UnknownType { index, ty })?;
+            requests.push(decoder(ty, &mut data).map_err(alloy_rlp::Error::from)?);
+            if !data.is_empty() {
+                return Err(RequestsDecodeError::InvalidLength { index, ty, len: entry.len() - 1 })
+            }
         }
+        Ok(Self(requests))
+    }
+
+    /// Decodes a list of requests like [`Decodable::decode`], but first checks that the length of
+    /// each entry is within the bounds of its request type.
+    ///
+    /// Entries of unknown type are not length checked.
+    pub fn decode_checked(buf: &mut &[u8]) -> Result<Self, RequestsDecodeError> {
+        let entries = <Vec<Bytes> as Decodable>::decode(buf)?;
+        let mut requests = Vec::with_capacity(entries.len());
+        for (index, entry) in entries.iter().enumerate() {
+            if let Some((&ty, data)) = entry.split_first() {
+                if let Some((min, max)) = request_data_len_bounds(ty) {
+                    if !(min..=max).contains(&data.len()) {
+                        return Err(RequestsDecodeError::InvalidLength {
+                            index,
+                            ty,
+                            len: data.len(),
+                        })
+                    }
+                }
+            }
+            requests
+                .push(Request::decode_7685(&mut entry.as_ref()).map_err(alloy_rlp::Error::from)?);
+        }
+        Ok(Self(requests))
+    }
+
+    /// Returns an iterator over the request data grouped by request type, as used by the engine
+    /// API.
+    ///
+    /// Yields one `(type, data)` pair per request type present, in ascending type order, where
+    /// `data` is the concatenation of the encoded payloads of all requests of that type. Each
+    /// chunk is only assembled when it is yielded.
+    pub fn execution_request_chunks(&self) -> impl Iterator<Item = (u8, Bytes)> + '_ {
+        let mut types = self.0.iter().map(|req| req.request_type()).collect::<Vec<_>>();
+        types.sort_unstable();
+        types.dedup();
+
+        types.into_iter().map(|ty| {
+            let mut data = Vec::new();
+            for req in self.0.iter().filter(|req| req.request_type() == ty) {
+                req.encode_payload_7685(&mut data);
+            }
+            (ty, data.into())
+        })
+    }
+
+    /// Returns the EIP-7685 encoded requests grouped by request type.
+    ///
+    /// Within each type the requests keep their relative order.
+    pub fn to_type_map(&self) -> BTreeMap<u8, Vec<Bytes>> {
+        let mut map = BTreeMap::<u8, Vec<Bytes>>::new();
+        for req in &self.0 {
+            map.entry(req.request_type()).or_default().push(req.encoded_7685().into());
+        }
+        map
+    }
+
+    /// Returns `true` if both lists contain the same requests of each type, ignoring the order of
+    /// requests of the same type.
+    ///
+    /// Unlike [`PartialEq`], reordering requests of the same type doesn't make the lists unequal.
+    /// Duplicates are significant, i.e. the requests of each type are compared as multisets.
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        if self.0.len() != other.0.len() {
+            return false
+        }
+        let sorted = |requests: &Self| {
+            let mut map = requests.to_type_map();
+            map.values_mut().for_each(|entries| entries.sort_unstable());
+            map
+        };
+        sorted(self) == sorted(other)
+    }
+
+    /// Decodes requests grouped by type, as returned by [`Self::to_type_map`], into a canonically
+    /// ordered list, i.e. sorted by request type.
+    ///
+    /// Fails if an entry can't be decoded or is not of the type it is grouped under.
+    pub fn from_type_map(map: BTreeMap<u8, Vec<Bytes>>) -> Result<Self, RequestsDecodeError> {
+        let mut requests = Vec::with_capacity(map.values().map(Vec::len).sum());
+        for (ty, entries) in map {
+            for entry in entries {
+                let req =
+                    Request::decode_7685(&mut entry.as_ref()).map_err(alloy_rlp::Error::from)?;
+                if req.request_type() != ty {
+                    return Err(
+                        alloy_rlp::Error::Custom("request type does not match its key").into()
+                    )
+                }
+                requests.push(req);
+            }
+        }
+        Ok(Self(requests))
+    }
+
+    /// Returns the length of the RLP list payload, i.e. of all requests encoded as RLP strings.
+    fn payload_length(&self) -> usize {
+        self.0
+            .iter()
+            .map(|req| {
+                let len = encoded_7685_len(req);
+                alloy_rlp::Header { list: false, payload_length: len }.length() + len
+            })
+            .sum()
+    }
+}
+
+/// EIP-6110 deposit request type.
+const DEPOSIT_REQUEST_TYPE: u8 = 0x00;
+
+/// EIP-7251 consolidation request type.
+const CONSOLIDATION_REQUEST_TYPE: u8 = 0x02;
+
+/// Returns the inclusive bounds of the length of the RLP encoded data of a request of the given
+/// type, or `None` if the type is unknown.
+const fn request_data_len_bounds(ty: u8) -> Option<(usize, usize)> {
+    /// Encoded length of a `u64` field: between one and nine bytes.
+    const U64: (usize, usize) = (1, 9);
+    /// Length of the RLP list header of the requests, all payloads are between 56 and 255 bytes.
+    const LIST_HEADER: usize = 2;
+
+    match ty {
+        // pubkey (48), withdrawal credentials (32), amount, signature (96), index
+        DEPOSIT_REQUEST_TYPE => {
+            let fixed = LIST_HEADER + (1 + 48) + (1 + 32) + (2 + 96);
+            Some((fixed + 2 * U64.0, fixed + 2 * U64.1))
+        }
+        // source address (20), validator pubkey (48), amount
+        WITHDRAWAL_REQUEST_TYPE => {
+            let fixed = LIST_HEADER + (1 + 20) + (1 + 48);
+            Some((fixed + U64.0, fixed + U64.1))
+        }
+        // source address (20), source pubkey (48), target pubkey (48)
+        CONSOLIDATION_REQUEST_TYPE => {
+            let fixed = LIST_HEADER + (1 + 20) + (1 + 48) + (1 + 48);
+            Some((fixed, fixed))
+        }
+        _ => None,
+    }
+}
+
+/// Decodes the data of a request of the given type, i.e. its EIP-7685 encoding without the type
+/// byte, see [`RequestTypeRegistry`].
+pub type RequestDecoder = fn(u8, &mut &[u8]) -> Result<Request, Eip7685Error>;
+
+/// Maps request types to the decoders used by [`Requests::decode_with_registry`].
+///
+/// The default registry decodes the request types known to [`Request`]. A decoder registered for
+/// another type must still produce one of the [`Request`] variants, so such a request is encoded
+/// again with the type of that variant rather than the type it was decoded from.
+#[derive(Debug, Clone)]
+pub struct RequestTypeRegistry {
+    decoders: BTreeMap<u8, RequestDecoder>,
+}
+
+impl RequestTypeRegistry {
+    /// Returns a registry without any decoders.
+    pub const fn empty() -> Self {
+        Self { decoders: BTreeMap::new() }
+    }
+
+    /// Registers the decoder for the given request type, returning the previously registered
+    /// decoder of that type, if any.
+    pub fn register(&mut self, ty: u8, decoder: RequestDecoder) -> Option<RequestDecoder> {
+        self.decoders.insert(ty, decoder)
+    }
+
+    /// Returns the decoder registered for the given request type.
+    pub fn decoder(&self, ty: u8) -> Option<RequestDecoder> {
+        self.decoders.get(&ty).copied()
+    }
+}
+
+impl Default for RequestTypeRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        for ty in [DEPOSIT_REQUEST_TYPE, WITHDRAWAL_REQUEST_TYPE, CONSOLIDATION_REQUEST_TYPE] {
+            registry.register(ty, Request::typed_decode);
+        }
+        registry
+    }
+}
+
+/// Builds a canonically ordered list of requests, rejecting duplicate deposit requests.
+///
+/// Two deposit requests are considered duplicates if they share the same validator pubkey and
+/// deposit index.
+#[derive(Debug, Clone, Default)]
+pub struct RequestsBuilder {
+    requests: Vec<Request>,
+    deposits: BTreeSet<(FixedBytes<48>, u64)>,
+}
+
+impl RequestsBuilder {
+    /// Adds a deposit request.
+    ///
+    /// Fails without adding the request if a deposit with the same pubkey and index was already
+    /// added.
+    pub fn add_deposit(&mut self, deposit: DepositRequest) -> Result<&mut Self, DuplicateDeposit> {
+        if !self.deposits.insert((deposit.pubkey, deposit.index)) {
+            return Err(DuplicateDeposit { pubkey: deposit.pubkey, index: deposit.index })
+        }
+        self.requests.push(Request::DepositRequest(deposit));
+        Ok(self)
+    }
+
+    /// Adds a request of any type, see [`Self::add_deposit`] for deposit requests.
+    pub fn add_request(&mut self, req: Request) -> Result<&mut Self, DuplicateDeposit> {
+        if let Request::DepositRequest(deposit) = req {
+            return self.add_deposit(deposit)
+        }
+        self.requests.push(req);
+        Ok(self)
+    }
+
+    /// Returns the requests ordered by request type, preserving the insertion order of requests
+    /// of the same type.
+    pub fn build(self) -> Requests {
+        let mut requests = self.requests;
+        requests.sort_by_key(|req| req.request_type());
+        Requests(requests)
+    }
+}
+
+/// Error returned by [`RequestsBuilder`] when a deposit request is added twice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror_no_std::Error)]
+#[error("duplicate deposit request for pubkey {pubkey} with index {index}")]
+pub struct DuplicateDeposit {
+    /// Validator pubkey of the rejected deposit.
+    pub pubkey: FixedBytes<48>,
+    /// Deposit index of the rejected deposit.
+    pub index: u64,
+}
+
+/// Error returned by [`Requests::push_typed`] when a request would break the canonical ordering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror_no_std::Error)]
+#[error("request of type {ty:#04x} can not follow a request of type {last:#04x}")]
+pub struct OrderingViolation {
+    /// Type of the rejected request.
+    pub ty: u8,
+    /// Type of the last request in the list.
+    pub last: u8,
+}
+
+/// Error returned by [`Requests::decode_checked`] and [`Requests::decode_with_registry`].
+#[derive(Debug, Clone, PartialEq, Eq, thiserror_no_std::Error)]
+pub enum RequestsDecodeError {
+    /// The requests could not be decoded.
+    #[error("{0}")]
+    Rlp(alloy_rlp::Error),
+    /// No decoder is registered for the type of a request, see
+    /// [`Requests::decode_with_registry`].
+    #[error("request {index} has unknown type {ty:#04x}")]
+    UnknownType {
+        /// Index of the request in the list.
+        index: usize,
+        /// Type of the request.
+        ty: u8,
+    },
+    /// The length of a request is out of bounds for its type.
+    #[error("request {index} of type {ty:#04x} has invalid length {len}")]
+    InvalidLength {
+        /// Index of the request in the list.
+        index: usize,
+        /// Type of the request.
+        ty: u8,
+        /// Length of the request data, excluding the type byte.
+        len: usize,
+    },
+}
+
+impl From<alloy_rlp::Error> for RequestsDecodeError {
+    fn from(err: alloy_rlp::Error) -> Self {
+        Self::Rlp(err)
+    }
+}
+
+/// Formats each request as `type=0xNN len=NN 0xdeadbeef…`, where `len` is the length of the
+/// request data and only the first bytes of the data are printed.
+///
+/// Entries are separated by `, `, or printed one per line with the alternate flag (`{:#}`).
+impl fmt::Display for Requests {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        /// Number of leading data bytes printed per request.
+        const PREVIEW_LEN: usize = 4;
+
+        for (idx, req) in self.0.iter().enumerate() {
+            if idx > 0 {
+                if f.alternate() {
+                    writeln!(f)?;
+                } else {
+                    write!(f, ", ")?;
+                }
+            }
+
+            let encoded = req.encoded_7685();
+            let data = &encoded[1..];
+            write!(f, "type=0x{:02x} len={} 0x", req.request_type(), data.len())?;
+            for byte in data.iter().take(PREVIEW_LEN) {
+                write!(f, "{byte:02x}")?;
+            }
+            if data.len() > PREVIEW_LEN {
+                write!(f, "…")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Number of request types counted individually by [`RequestsSummary`], the types `0x00` to
+/// `0x02`.
+pub const SUMMARY_REQUEST_TYPES: usize = 3;
+
+/// Request counts of a list of requests, see [`Requests::summary`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestsSummary {
+    /// Total number of requests.
+    pub total: usize,
+    /// Number of requests per type, indexed by request type. Requests of higher types are only
+    /// counted in `total`.
+    pub per_type: [usize; SUMMARY_REQUEST_TYPES],
+}
+
+/// Formats the summary as `3 requests (0x00: 1, 0x01: 2, 0x02: 0)`.
+impl fmt::Display for RequestsSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} requests (", self.total)?;
+        for (ty, count) in self.per_type.iter().enumerate() {
+            if ty > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "0x{ty:02x}: {count}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Returns the length of the EIP-7685 encoding of the request, including the type byte.
+fn encoded_7685_len(req: &Request) -> usize {
+    match req {
+        Request::DepositRequest(req) => 1 + req.length(),
+        Request::WithdrawalRequest(req) => 1 + req.length(),
+        Request::ConsolidationRequest(req) => 1 + req.length(),
+        _ => req.encoded_7685().len(),
+    }
+}
+
+impl Encodable for Requests {
+    fn encode(&self, out: &mut dyn bytes::BufMut) {
+        alloy_rlp::Header { list: true, payload_length: self.payload_length() }.encode(out);
+        for req in &self.0 {
+            alloy_rlp::Header { list: false, payload_length: encoded_7685_len(req) }.encode(out);
+            req.encode_7685(out);
+        }
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
     }
 }
 
@@ -42,3 +509,313 @@ impl Decodable for Requests {
             .map(Self)?)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_eips::eip7002::WithdrawalRequest;
+    use alloy_primitives::{hex, Address};
+
+    fn withdrawal_request(amount: u64) -> Request {
+        Request::WithdrawalRequest(WithdrawalRequest {
+            source_address: Address::with_last_byte(1),
+            validator_pubkey: FixedBytes::<48>::from([1; 48]),
+            amount,
+        })
+    }
+
+    #[test]
+    fn display_pretty() {
+        let requests = Requests(vec![withdrawal_request(1), withdrawal_request(2)]);
+
+        let entry = |req: &Request| {
+            let encoded = req.encoded_7685();
+            format!("type=0x01 len={} 0x{}…", encoded.len() - 1, hex::encode(&encoded[1..5]))
+        };
+        let first = entry(&requests[0]);
+        let second = entry(&requests[1]);
+
+        assert_eq!(format!("{requests:#}"), format!("{first}\n{second}"));
+        assert_eq!(format!("{requests}"), format!("{first}, {second}"));
+        assert_eq!(Requests::default().to_string(), "");
+    }
+
+    #[test]
+    fn clear_retains_capacity() {
+        let mut requests = Requests::with_capacity(8);
+        let capacity = requests.capacity();
+        assert!(capacity >= 8);
+
+        requests.push(withdrawal_request(1));
+        requests.push(withdrawal_request(2));
+        requests.clear();
+
+        assert!(requests.is_empty());
+        assert_eq!(requests.capacity(), capacity);
+    }
+
+    #[test]
+    fn decode_checked_rejects_invalid_length() {
+        let deposit = Request::DepositRequest(DepositRequest { amount: 1, ..Default::default() });
+        let withdrawal = withdrawal_request(u64::MAX);
+
+        let mut malformed = deposit.encoded_7685();
+        malformed.extend_from_slice(&[0; 32]);
+        let len = malformed.len() - 1;
+        let entries = vec![Bytes::from(withdrawal.encoded_7685()), malformed.into()];
+        let encoded = alloy_rlp::encode(&entries);
+        assert_eq!(
+            Requests::decode_checked(&mut encoded.as_slice()),
+            Err(RequestsDecodeError::InvalidLength { index: 1, ty: 0x00, len })
+        );
+
+        let entries = vec![Bytes::from(withdrawal.encoded_7685()), deposit.encoded_7685().into()];
+        let encoded = alloy_rlp::encode(&entries);
+        assert_eq!(
+            Requests::decode_checked(&mut encoded.as_slice()),
+            Ok(Requests(vec![withdrawal, deposit]))
+        );
+    }
+
+    #[test]
+    fn encoded_length() {
+        let deposit =
+            || Request::DepositRequest(DepositRequest { amount: 1, ..Default::default() });
+        let sets = [
+            vec![],
+            vec![withdrawal_request(1)],
+            vec![deposit(), withdrawal_request(u64::MAX)],
+            vec![deposit(); 4],
+        ];
+
+        for requests in sets.map(Requests) {
+            let encoded = alloy_rlp::encode(&requests);
+            assert_eq!(requests.length(), encoded.len());
+            assert_eq!(Requests::decode(&mut encoded.as_slice()), Ok(requests));
+        }
+    }
+
+    #[test]
+    fn encoded_byte_len() {
+        let deposit = Request::DepositRequest(DepositRequest { amount: 1, ..Default::default() });
+        let requests = Requests(vec![deposit, withdrawal_request(1), withdrawal_request(u64::MAX)]);
+
+        let expected = requests.iter().map(|req| req.encoded_7685().len()).sum::<usize>();
+        assert_eq!(requests.encoded_byte_len(), expected);
+        assert!(requests.encoded_byte_len() < requests.length());
+        assert_eq!(Requests::default().encoded_byte_len(), 0);
+    }
+
+    #[test]
+    fn execution_request_chunks() {
+        let deposit = |index| {
+            Request::DepositRequest(DepositRequest { index, amount: 1, ..Default::default() })
+        };
+        let requests =
+            Requests(vec![withdrawal_request(1), deposit(0), withdrawal_request(2), deposit(1)]);
+
+        let payload = |req: &Request| req.encoded_7685()[1..].to_vec();
+        let chunks = requests.execution_request_chunks().collect::<Vec<_>>();
+        assert_eq!(
+            chunks,
+            vec![
+                (0x00, [payload(&requests[1]), payload(&requests[3])].concat().into()),
+                (0x01, [payload(&requests[0]), payload(&requests[2])].concat().into()),
+            ]
+        );
+
+        assert_eq!(Requests::default().execution_request_chunks().count(), 0);
+    }
+
+    #[test]
+    fn type_map_roundtrip() {
+        let deposit = |index| {
+            Request::DepositRequest(DepositRequest { index, amount: 1, ..Default::default() })
+        };
+        let requests = Requests(vec![deposit(0), deposit(1), withdrawal_request(1)]);
+
+        let map = requests.to_type_map();
+        assert_eq!(map.keys().copied().collect::<Vec<_>>(), vec![0x00, 0x01]);
+        assert_eq!(map[&0x00].len(), 2);
+        assert_eq!(Requests::from_type_map(map).unwrap(), requests);
+
+        // non-canonical input comes out ordered by type
+        let unordered = Requests(vec![withdrawal_request(1), deposit(0), deposit(1)]);
+        assert_eq!(Requests::from_type_map(unordered.to_type_map()).unwrap(), requests);
+
+        // entries must match their key
+        let mut map = requests.to_type_map();
+        let withdrawals = map.remove(&0x01).unwrap();
+        map.insert(0x00, withdrawals);
+        assert!(Requests::from_type_map(map).is_err());
+    }
+
+    #[test]
+    fn push_typed() {
+        let deposit = || Request::DepositRequest(DepositRequest::default());
+
+        let mut requests = Requests::default();
+        requests.push_typed(deposit()).unwrap();
+        requests.push_typed(deposit()).unwrap();
+        requests.push_typed(withdrawal_request(1)).unwrap();
+        requests.push_typed(withdrawal_request(2)).unwrap();
+        assert_eq!(requests.len(), 4);
+
+        assert_eq!(requests.push_typed(deposit()), Err(OrderingViolation { ty: 0x00, last: 0x01 }));
+        assert_eq!(requests.len(), 4);
+    }
+
+    #[test]
+    fn consolidation_requests() {
+        let consolidation = Request::ConsolidationRequest(ConsolidationRequest {
+            source_address: Address::with_last_byte(1),
+            source_pubkey: FixedBytes::<48>::from([1; 48]),
+            target_pubkey: FixedBytes::<48>::from([2; 48]),
+        });
+        let encoded = consolidation.encoded_7685();
+        assert_eq!(encoded[0], CONSOLIDATION_REQUEST_TYPE);
+        assert_eq!(
+            request_data_len_bounds(CONSOLIDATION_REQUEST_TYPE),
+            Some((encoded.len() - 1, encoded.len() - 1))
+        );
+
+        let list = alloy_rlp::encode(vec![
+            Bytes::from(withdrawal_request(1).encoded_7685()),
+            Bytes::from(encoded),
+        ]);
+        let requests = Requests::decode_checked(&mut list.as_slice()).unwrap();
+        assert_eq!(requests[1], consolidation);
+        assert_eq!(
+            requests.consolidation_requests().collect::<Vec<_>>(),
+            vec![consolidation.as_consolidation_request().unwrap()]
+        );
+    }
+
+    #[test]
+    fn unknown_types() {
+        let consolidation = || Request::ConsolidationRequest(ConsolidationRequest::default());
+        let requests = Requests(vec![
+            Request::DepositRequest(DepositRequest::default()),
+            withdrawal_request(1),
+            consolidation(),
+            consolidation(),
+        ]);
+
+        let known = [DEPOSIT_REQUEST_TYPE, WITHDRAWAL_REQUEST_TYPE];
+        assert_eq!(requests.unknown_types(&known), vec![CONSOLIDATION_REQUEST_TYPE]);
+        assert_eq!(requests.unknown_types(&[]), vec![0x00, 0x01, 0x02]);
+        assert!(requests.unknown_types(&[0x00, 0x01, 0x02]).is_empty());
+    }
+
+    #[test]
+    fn semantically_eq() {
+        let deposit = Request::DepositRequest(DepositRequest::default());
+        let requests = Requests(vec![deposit, withdrawal_request(1), withdrawal_request(2)]);
+        let reordered = Requests(vec![deposit, withdrawal_request(2), withdrawal_request(1)]);
+        assert_ne!(requests, reordered);
+        assert!(requests.semantically_eq(&reordered));
+        assert!(reordered.semantically_eq(&requests));
+
+        let duplicated = Requests(vec![deposit, withdrawal_request(1), withdrawal_request(1)]);
+        assert!(!requests.semantically_eq(&duplicated));
+        assert!(!requests.semantically_eq(&Requests(vec![deposit, withdrawal_request(1)])));
+    }
+
+    #[test]
+    fn summary() {
+        let requests = Requests(vec![
+            Request::DepositRequest(DepositRequest::default()),
+            withdrawal_request(1),
+            withdrawal_request(2),
+        ]);
+
+        let summary = requests.summary();
+        assert_eq!(summary, RequestsSummary { total: 3, per_type: [1, 2, 0] });
+        assert_eq!(summary.to_string(), "3 requests (0x00: 1, 0x01: 2, 0x02: 0)");
+        assert_eq!(Requests::default().summary(), RequestsSummary::default());
+    }
+
+    #[test]
+    fn decode_with_registry() {
+        const CUSTOM_TYPE: u8 = 0xff;
+
+        let withdrawal = withdrawal_request(1);
+        let mut entry = withdrawal.encoded_7685();
+        entry[0] = CUSTOM_TYPE;
+        let encoded = alloy_rlp::encode(vec![Bytes::from(entry)]);
+
+        let mut registry = RequestTypeRegistry::default();
+        assert_eq!(
+            Requests::decode_with_registry(&mut encoded.as_ref(), &registry),
+            Err(RequestsDecodeError::UnknownType { index: 0, ty: CUSTOM_TYPE })
+        );
+
+        let decoder: RequestDecoder =
+            |_, buf| Ok(Request::WithdrawalRequest(WithdrawalRequest::decode(buf)?));
+        assert!(registry.register(CUSTOM_TYPE, decoder).is_none());
+        assert_eq!(
+            Requests::decode_with_registry(&mut encoded.as_ref(), &registry),
+            Ok(Requests(vec![withdrawal]))
+        );
+        // the request is encoded again as a withdrawal request
+        assert_ne!(alloy_rlp::encode(&Requests(vec![withdrawal])), encoded);
+
+        // known types decode like `Decodable::decode`
+        let requests =
+            Requests(vec![Request::DepositRequest(DepositRequest::default()), withdrawal]);
+        let encoded = alloy_rlp::encode(&requests);
+        assert_eq!(
+            Requests::decode_with_registry(&mut encoded.as_ref(), &RequestTypeRegistry::default()),
+            Ok(requests)
+        );
+
+        // the decoder must consume the whole entry
+        let mut entry = withdrawal.encoded_7685();
+        entry.push(0x80);
+        let len = entry.len() - 1;
+        let encoded = alloy_rlp::encode(vec![Bytes::from(entry)]);
+        assert_eq!(
+            Requests::decode_with_registry(&mut encoded.as_ref(), &RequestTypeRegistry::default()),
+            Err(RequestsDecodeError::InvalidLength { index: 0, ty: WITHDRAWAL_REQUEST_TYPE, len })
+        );
+    }
+
+    #[test]
+    fn requests_builder() {
+        let deposit = |index| DepositRequest {
+            pubkey: FixedBytes::<48>::from([1; 48]),
+            index,
+            ..Default::default()
+        };
+
+        let mut builder = RequestsBuilder::default();
+        builder.add_request(withdrawal_request(1)).unwrap();
+        builder.add_deposit(deposit(0)).unwrap().add_deposit(deposit(1)).unwrap();
+        assert_eq!(
+            builder.add_deposit(deposit(0)).unwrap_err(),
+            DuplicateDeposit { pubkey: FixedBytes::from([1; 48]), index: 0 }
+        );
+        assert!(builder.add_request(Request::DepositRequest(deposit(1))).is_err());
+
+        assert_eq!(
+            builder.build(),
+            Requests(vec![
+                Request::DepositRequest(deposit(0)),
+                Request::DepositRequest(deposit(1)),
+                withdrawal_request(1),
+            ])
+        );
+    }
+
+    #[test]
+    fn requests_hash() {
+        assert_eq!(EMPTY_REQUESTS_HASH, B256::from_slice(&Sha256::digest([])));
+        assert_eq!(compute_requests_hash(&Requests::default()), EMPTY_REQUESTS_HASH);
+
+        let requests = Requests(vec![withdrawal_request(1)]);
+        let mut chunk = vec![WITHDRAWAL_REQUEST_TYPE];
+        requests[0].encode_payload_7685(&mut chunk);
+        let expected = Sha256::digest(Sha256::digest(&chunk));
+        assert_eq!(compute_requests_hash(&requests), B256::from_slice(&expected));
+    }
+}
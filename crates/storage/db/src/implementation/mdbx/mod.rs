@@ -89,6 +89,12 @@ pub struct DatabaseArguments {
     ///
     /// This flag affects only at environment opening but can't be changed after.
     exclusive: Option<bool>,
+    /// Prefault all database pages into memory after opening the environment.
+    warm: bool,
+    /// Enable OS read-ahead on the database file. If [None], read-ahead is disabled.
+    ///
+    /// Read-ahead improves performance for linear scans, but worsens it for random access.
+    readahead: Option<bool>,
 }
 
 impl DatabaseArguments {
@@ -99,6 +105,8 @@ impl DatabaseArguments {
             log_level: None,
             max_read_transaction_duration: None,
             exclusive: None,
+            warm: false,
+            readahead: None,
         }
     }
 
@@ -123,6 +131,28 @@ impl DatabaseArguments {
         self
     }
 
+    /// Set whether to prefault all database pages into memory after opening the environment.
+    pub const fn with_warm(mut self, warm: bool) -> Self {
+        self.warm = warm;
+        self
+    }
+
+    /// Returns whether all database pages are prefaulted into memory after opening.
+    pub const fn warm(&self) -> bool {
+        self.warm
+    }
+
+    /// Set whether to enable OS read-ahead on the database file.
+    pub const fn with_readahead(mut self, readahead: Option<bool>) -> Self {
+        self.readahead = readahead;
+        self
+    }
+
+    /// Returns whether OS read-ahead on the database file is enabled, if configured.
+    pub const fn readahead(&self) -> Option<bool> {
+        self.readahead
+    }
+
     /// Returns the client version if any.
     pub const fn client_version(&self) -> &ClientVersion {
         &self.client_version
@@ -336,9 +366,9 @@ impl DatabaseEnv {
 
         inner_env.set_flags(EnvironmentFlags {
             mode,
-            // We disable readahead because it improves performance for linear scans, but
-            // worsens it for random access (which is our access pattern outside of sync)
-            no_rdahead: true,
+            // We disable readahead by default because it improves performance for linear scans,
+            // but worsens it for random access (which is our access pattern outside of sync)
+            no_rdahead: !args.readahead.unwrap_or_default(),
             coalesce: true,
             exclusive: args.exclusive.unwrap_or_default(),
             ..Default::default()
@@ -408,6 +438,10 @@ impl DatabaseEnv {
             _lock_file,
         };
 
+        if args.warm {
+            env.inner.warmup().map_err(|e| DatabaseError::Open(e.into()))?;
+        }
+
         Ok(env)
     }
 
@@ -152,6 +152,21 @@ impl Environment {
         mdbx_result(unsafe { ffi::mdbx_env_sync_ex(self.env_ptr(), force, false) })
     }
 
+    /// Loads the allocated portion of the database into memory by sequentially touching all of its
+    /// pages, using system calls so that a lack of memory doesn't get the process killed.
+    ///
+    /// Returns `true` if not all pages could be loaded, see `mdbx_env_warmup`.
+    pub fn warmup(&self) -> Result<bool> {
+        mdbx_result(unsafe {
+            ffi::mdbx_env_warmup(
+                self.env_ptr(),
+                ptr::null(),
+                ffi::MDBX_warmup_force | ffi::MDBX_warmup_oomsafe,
+                0,
+            )
+        })
+    }
+
     /// Retrieves statistics about this environment.
     pub fn stat(&self) -> Result<Stat> {
         unsafe {
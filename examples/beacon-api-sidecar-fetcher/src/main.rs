@@ -20,7 +20,7 @@ use std::{
 
 use clap::Parser;
 use futures_util::{stream::FuturesUnordered, StreamExt};
-use mined_sidecar::MinedSidecarStream;
+use mined_sidecar::{MinedSidecarStream, OverflowPolicy};
 use reth::{builder::NodeHandle, cli::Cli, primitives::B256, providers::CanonStateSubscriptions};
 use reth_node_ethereum::EthereumNode;
 
@@ -45,6 +45,10 @@ fn main() {
                 client: reqwest::Client::new(),
                 pending_requests: FuturesUnordered::new(),
                 queued_actions: VecDeque::new(),
+                queue_capacity: 1024,
+                overflow_policy: OverflowPolicy::DropOldest,
+                held_back: VecDeque::new(),
+                dropped: 0,
             };
 
             while let Some(result) = sidecar_stream.next().await {
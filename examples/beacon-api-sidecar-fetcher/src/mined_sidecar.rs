@@ -39,6 +39,21 @@ pub struct ReorgedBlob {
 pub enum BlobTransactionEvent {
     Mined(MinedBlob),
     Reorged(ReorgedBlob),
+    /// Events were dropped because the queue was full, see [`OverflowPolicy::DropOldest`].
+    Dropped {
+        count: usize,
+    },
+}
+
+/// What to do when the queue of [`BlobTransactionEvent`]s of a [`MinedSidecarStream`] is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Drop the oldest queued events to make room for new ones.
+    DropOldest,
+    /// Stop polling for new canonical state notifications until the queue has room again. Events
+    /// of an already polled notification that don't fit are held back and queued once there is
+    /// room.
+    Block,
 }
 
 /// SideCarError Handles Errors from both EL and CL
@@ -85,6 +100,60 @@ pub struct MinedSidecarStream<St, P> {
     pub client: reqwest::Client,
     pub pending_requests: FuturesUnordered<SidecarsFuture>,
     pub queued_actions: VecDeque<BlobTransactionEvent>,
+    /// Maximum number of events in `queued_actions`
+    pub queue_capacity: usize,
+    pub overflow_policy: OverflowPolicy,
+    /// Events that did not fit into `queued_actions` under [`OverflowPolicy::Block`]
+    pub held_back: VecDeque<BlobTransactionEvent>,
+    /// Number of events dropped since the last [`BlobTransactionEvent::Dropped`]
+    pub dropped: usize,
+}
+
+impl<St, P> MinedSidecarStream<St, P> {
+    /// Returns `true` if no more events should be queued.
+    fn is_full(&self) -> bool {
+        self.queued_actions.len() >= self.queue_capacity
+    }
+
+    /// Queues the event if the queue has room. Otherwise the oldest queued event is dropped under
+    /// [`OverflowPolicy::DropOldest`], and the event is held back under [`OverflowPolicy::Block`].
+    fn queue_action(&mut self, action: BlobTransactionEvent) {
+        match self.overflow_policy {
+            OverflowPolicy::DropOldest => {
+                if self.is_full() {
+                    self.queued_actions.pop_front();
+                    self.dropped += 1;
+                }
+            }
+            OverflowPolicy::Block => {
+                if self.is_full() || !self.held_back.is_empty() {
+                    self.held_back.push_back(action);
+                    return
+                }
+            }
+        }
+        self.queued_actions.push_back(action);
+    }
+
+    /// Returns the next event to emit, reporting dropped events first.
+    ///
+    /// Moves a held back event into the queue for every event taken from it.
+    fn next_queued(&mut self) -> Option<BlobTransactionEvent> {
+        if self.dropped > 0 {
+            return Some(BlobTransactionEvent::Dropped { count: std::mem::take(&mut self.dropped) })
+        }
+        let next = self.queued_actions.pop_front()?;
+        if let Some(held) = self.held_back.pop_front() {
+            self.queued_actions.push_back(held);
+        }
+        Some(next)
+    }
+
+    /// Returns `true` if new events can be pulled in.
+    fn can_poll_events(&self) -> bool {
+        self.overflow_policy != OverflowPolicy::Block ||
+            (!self.is_full() && self.held_back.is_empty())
+    }
 }
 
 impl<St, P> MinedSidecarStream<St, P>
@@ -130,7 +199,9 @@ where
 
         // if any blob is missing we must instead query the consensus layer.
         if all_blobs_available {
-            self.queued_actions.extend(actions_to_queue);
+            for action in actions_to_queue {
+                self.queue_action(action);
+            }
         } else {
             let client_clone = self.client.clone();
             let block_root = block.hash();
@@ -156,29 +227,45 @@ where
 
         // Request locally first, otherwise request from CL
         loop {
-            if let Some(mined_sidecar) = this.queued_actions.pop_front() {
+            if let Some(mined_sidecar) = this.next_queued() {
                 return Poll::Ready(Some(Ok(mined_sidecar)))
             }
 
             // Check if any pending requests are ready and append to buffer
-            while let Poll::Ready(Some(pending_result)) = this.pending_requests.poll_next_unpin(cx)
-            {
+            while this.can_poll_events() {
+                let Poll::Ready(Some(pending_result)) = this.pending_requests.poll_next_unpin(cx)
+                else {
+                    break
+                };
                 match pending_result {
                     Ok(mined_sidecars) => {
                         for sidecar in mined_sidecars {
-                            this.queued_actions.push_back(sidecar);
+                            this.queue_action(sidecar);
                         }
                     }
                     Err(err) => return Poll::Ready(Some(Err(err))),
                 }
             }
 
-            while let Poll::Ready(Some(notification)) = this.events.poll_next_unpin(cx) {
+            while this.can_poll_events() {
+                let Poll::Ready(Some(notification)) = this.events.poll_next_unpin(cx) else {
+                    break
+                };
                 {
                     match notification {
-                        CanonStateNotification::Commit { new } => {
-                            for (_, block) in new.blocks().iter() {
-                                this.process_block(block);
+                        CanonStateNotification::Commit { .. } => {
+                            // only blocks with blob transactions need their sidecars fetched
+                            let mut numbers = blob_tx_hashes(&notification)
+                                .into_iter()
+                                .map(|(number, _)| number)
+                                .collect::<Vec<_>>();
+                            numbers.dedup();
+
+                            let committed = notification.committed();
+                            for number in numbers {
+                                if let Some(block) = committed.blocks().get(&number) {
+                                    this.process_block(block);
+                                }
                             }
                         }
                         CanonStateNotification::Reorg { old, new } => {
@@ -202,7 +289,9 @@ where
                                         })
                                     })
                                     .collect();
-                                this.queued_actions.extend(txs);
+                                for tx in txs {
+                                    this.queue_action(tx);
+                                }
                             }
 
                             for (_, block) in new.blocks().iter() {
@@ -216,6 +305,21 @@ where
     }
 }
 
+/// Returns the block number and hash of every blob transaction in the blocks committed by the
+/// notification.
+///
+/// Blob sidecars are fetched per block from the beacon node, so the block number is what
+/// identifies the fetch a transaction belongs to.
+pub fn blob_tx_hashes(notification: &CanonStateNotification) -> Vec<(u64, B256)> {
+    notification
+        .committed()
+        .blocks_iter()
+        .flat_map(|block| {
+            block.transactions().filter(|tx| tx.is_eip4844()).map(|tx| (block.number, tx.hash()))
+        })
+        .collect()
+}
+
 /// Query the Beacon Layer for missing BlobTransactions
 async fn fetch_blobs_for_block(
     client: reqwest::Client,
@@ -276,3 +380,107 @@ async fn fetch_blobs_for_block(
 
     Ok(sidecars)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::stream;
+    use reth::{
+        primitives::{
+            Address, Header, SealedBlock, Signature, Transaction, TransactionSigned, TxEip1559,
+            TxEip4844,
+        },
+        providers::{Chain, ExecutionOutcome},
+    };
+    use std::sync::Arc;
+
+    fn reorged(n: u8) -> BlobTransactionEvent {
+        BlobTransactionEvent::Reorged(ReorgedBlob {
+            transaction_hash: B256::with_last_byte(n),
+            block_metadata: BlockMetadata { block_hash: B256::ZERO, block_number: 0, gas_used: 0 },
+        })
+    }
+
+    fn sidecar_stream(
+        overflow_policy: OverflowPolicy,
+    ) -> MinedSidecarStream<stream::Empty<CanonStateNotification>, ()> {
+        MinedSidecarStream {
+            events: stream::empty(),
+            pool: (),
+            beacon_config: BeaconSidecarConfig::default(),
+            client: reqwest::Client::new(),
+            pending_requests: FuturesUnordered::new(),
+            queued_actions: VecDeque::new(),
+            queue_capacity: 2,
+            overflow_policy,
+            held_back: VecDeque::new(),
+            dropped: 0,
+        }
+    }
+
+    #[test]
+    fn queue_drops_oldest_past_capacity() {
+        let mut stream = sidecar_stream(OverflowPolicy::DropOldest);
+        for n in 0..5 {
+            stream.queue_action(reorged(n));
+        }
+        assert_eq!(stream.queued_actions.len(), 2);
+
+        assert!(matches!(stream.next_queued(), Some(BlobTransactionEvent::Dropped { count: 3 })));
+        for n in 3..5 {
+            assert!(matches!(
+                stream.next_queued(),
+                Some(BlobTransactionEvent::Reorged(ReorgedBlob { transaction_hash, .. }))
+                    if transaction_hash == B256::with_last_byte(n)
+            ));
+        }
+        assert!(stream.next_queued().is_none());
+    }
+
+    #[test]
+    fn queue_blocks_past_capacity() {
+        let mut stream = sidecar_stream(OverflowPolicy::Block);
+        for n in 0..3 {
+            assert_eq!(stream.can_poll_events(), n < 2);
+            stream.queue_action(reorged(n));
+        }
+        // the event past capacity is held back
+        assert_eq!(stream.queued_actions.len(), 2);
+        assert_eq!(stream.held_back.len(), 1);
+        assert!(!stream.can_poll_events());
+
+        for n in 0..3 {
+            assert!(matches!(
+                stream.next_queued(),
+                Some(BlobTransactionEvent::Reorged(ReorgedBlob { transaction_hash, .. }))
+                    if transaction_hash == B256::with_last_byte(n)
+            ));
+            assert!(stream.queued_actions.len() <= 2);
+            assert_eq!(stream.can_poll_events(), n > 0);
+        }
+        assert!(stream.next_queued().is_none());
+    }
+
+    #[test]
+    fn blob_tx_hashes_from_commit() {
+        let blob_tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Eip4844(TxEip4844::default()),
+            Signature::default(),
+        );
+        let regular_tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Eip1559(TxEip1559::default()),
+            Signature::default(),
+        );
+        let block = SealedBlock {
+            header: Header { number: 1, ..Default::default() }.seal_slow(),
+            body: vec![regular_tx, blob_tx.clone()],
+            ..Default::default()
+        };
+        let block = SealedBlockWithSenders::new(block, vec![Address::ZERO; 2]).unwrap();
+
+        let notification = CanonStateNotification::Commit {
+            new: Arc::new(Chain::from_block(block, ExecutionOutcome::default(), None)),
+        };
+        assert_eq!(blob_tx_hashes(&notification), vec![(1, blob_tx.hash())]);
+    }
+}